@@ -1,17 +1,31 @@
 use clap::Parser;
 use longcut_config::{ConfigError, ConfigModule, Module};
 use longcut_core::CoreModule;
+use longcut_core::port::input::InputError;
+use longcut_filesystem::FilesystemModule;
+use longcut_filesystem_adapter_longcut_core::LocalFilesystem;
 use longcut_gui::GuiModule;
 use longcut_gui_adapter_longcut_core::GuiView;
 use longcut_shell::ShellModule;
 use longcut_shell_adapter_longcut_core::ShellExecutor;
-use longcut_x11::X11Module;
+use longcut_state::StateModule;
+use longcut_state_adapter_longcut_core::StateStorage;
+use longcut_x11::{X11Error, X11Module};
 use longcut_x11_adapter_longcut_core::{X11Input, X11WindowManager};
 use longcut_xcb::XcbModule;
+use longcut_xcb_adapter_longcut_core::XcbClipboard;
 use longcut_xcb_adapter_longcut_gui::XcbWindowManager;
 use std::fmt::Debug;
 use std::path::PathBuf;
 use std::process::exit;
+use std::thread;
+use std::time::Duration;
+
+/// Delay between the attempts to reconnect to the X server after losing the connection.
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// How many times reconnecting to the X server is attempted before giving up.
+const RECONNECT_ATTEMPTS: u32 = 30;
 
 #[derive(Parser)]
 struct Args {
@@ -24,7 +38,16 @@ struct Args {
     check_config_only: bool,
 }
 
+/// The modules connected to the X server, which are set up anew whenever the connection is lost.
+struct DisplayModules {
+    x11: X11Module,
+    xcb: XcbModule,
+}
+
 fn main() {
+    // The input methods of the X server follow the character encoding of the locale.
+    unsafe { libc::setlocale(libc::LC_CTYPE, c"".as_ptr()) };
+
     let args = Args::parse();
 
     if args.check_config_only {
@@ -89,28 +112,92 @@ fn launch_application(args: &Args) {
 
     let config = unwrap_module(ConfigModule::new(config_file));
 
-    let x11 = X11Module::new();
-
-    let xcb = XcbModule::new();
+    let mut display = connect_display().unwrap_or_else(|error| exit_with_error(&error));
 
     let shell = unwrap_module(ShellModule::new(&config));
 
-    let xcb_gui_window_manager = XcbWindowManager::new(&xcb.xcb_service);
-    let gui = unwrap_module(GuiModule::new(&config, &xcb_gui_window_manager));
+    let state = StateModule::new();
 
-    let x11_input = X11Input::new(&x11.x11_handle);
-    let x11_window_manager = X11WindowManager::new(&x11.x11_handle);
+    let filesystem = FilesystemModule::new();
+
+    loop {
+        let error = run_on_display(&config, &display, &shell, &state, &filesystem);
+        eprintln!("Lost the connection to the X server ({error:?}), reconnecting...");
+
+        // The old connections are closed before opening new ones.
+        drop(display);
+        display = reconnect_display();
+    }
+}
+
+/// Runs the application on the display until the connection to it is lost.
+fn run_on_display(
+    config: &ConfigModule,
+    display: &DisplayModules,
+    shell: &ShellModule,
+    state: &StateModule,
+    filesystem: &FilesystemModule,
+) -> InputError {
+    let xcb_gui_window_manager = XcbWindowManager::new(&display.xcb.xcb_service);
+    let gui = unwrap_module(GuiModule::new(config, &xcb_gui_window_manager));
+
+    let x11_input = X11Input::new(&display.x11.x11_handle);
+    let x11_window_manager = X11WindowManager::new(&display.x11.x11_handle);
     let gui_view = GuiView::new(&gui.gui_service);
     let shell_executor = ShellExecutor::new(&shell.service);
+    let state_storage = StateStorage::new(&state.state_service);
+    let local_filesystem = LocalFilesystem::new(&filesystem.filesystem_service);
+    let xcb_clipboard = XcbClipboard::new(&display.xcb.xcb_service);
     let core = unwrap_module(CoreModule::new(
-        &config,
+        config,
         &x11_input,
         &gui_view,
         &shell_executor,
         &x11_window_manager,
+        &state_storage,
+        &local_filesystem,
+        &xcb_clipboard,
     ));
 
-    core.longcut_service.run_forever();
+    core.longcut_service.run()
+}
+
+/// Connects to the X server, describing the reason on failure.
+fn connect_display() -> Result<DisplayModules, String> {
+    let x11 = X11Module::new().map_err(|error| match error {
+        X11Error::DisplayUnavailable(None) => {
+            "The DISPLAY environment variable is not set, so there is no X server to connect to!"
+                .into()
+        }
+        X11Error::DisplayUnavailable(Some(display)) => {
+            format!("Could not connect to the X server of DISPLAY \"{display}\"!")
+        }
+        error => format!("x11 module initialization failed.\n\nCause: {error:?}"),
+    })?;
+
+    let xcb = XcbModule::new().map_err(|error| {
+        let module_name = XcbModule::IDENTIFIER;
+        format!("{module_name} module initialization failed.\n\nCause: {error:?}")
+    })?;
+
+    Ok(DisplayModules { x11, xcb })
+}
+
+/// Waits for the X server to come back, such as after a restart. Stops the program if it does not
+/// come back in time.
+fn reconnect_display() -> DisplayModules {
+    let mut last_error = String::new();
+    for _ in 0..RECONNECT_ATTEMPTS {
+        thread::sleep(RECONNECT_DELAY);
+        match connect_display() {
+            Ok(display) => return display,
+            Err(error) => last_error = error,
+        }
+    }
+
+    exit_with_error(&format!(
+        "Could not reconnect to the X server.\n\nCause: {last_error}"
+    ));
 }
 
 fn resolve_config_file_location(args: &Args) -> Option<PathBuf> {
@@ -1,20 +1,33 @@
-use crate::model::command::{Command, CommandError, CommandParameter};
+use crate::model::activation::{
+    Activation, ActivationKey, DEFAULT_DOUBLE_TAP_INTERVAL, DEFAULT_TAP_DURATION,
+};
+use crate::model::command::{Command, CommandError, CommandParameter, DEFAULT_HOLD_DURATION};
 use crate::model::effect::{EffectTemplate, ShellCommandTemplate};
 use crate::model::key::{Key, Modifier, Symbol};
 use crate::model::layer::Layer;
+use crate::model::option_source::{JsonSelector, OptionSource, OutputFormat, Separator};
+use crate::model::options_command::OptionsCommandTemplate;
 use crate::model::parameter::{
-    CharacterParameter, ChooseParameter, ParameterDefinitionVariant, TextParameter,
+    CharacterParameter, ChooseParameter, ParameterDefinitionVariant, PathKind, PathParameter,
+    SecretParameter, TextParameter,
 };
+use crate::model::selection::Selection;
+use crate::model::shortcut_map::GUARANTEED_MNEMONIC_COUNT;
+use crate::model::window::WindowField;
 use itertools::Itertools;
 use serde::Deserialize;
+use std::time::Duration;
 
 #[derive(Debug, Deserialize)]
 #[serde(try_from = "ConfigSchema")]
 pub struct Config {
-    pub keys_activate: Vec<Key>,
-    pub keys_app_activate: Vec<Key>,
+    pub keys_activate: Vec<ActivationKey>,
+    pub keys_app_activate: Vec<ActivationKey>,
     pub keys_back: Vec<Key>,
     pub keys_deactivate: Vec<Key>,
+    pub options_per_page: usize,
+    /// How long a key must be held to run the hold action of its command.
+    pub hold_duration: Duration,
     pub root_layer: Layer,
     pub app_specific_layers: Vec<ApplicationConfig>,
 }
@@ -27,12 +40,14 @@ pub struct ApplicationConfig {
 
 #[derive(Debug, Deserialize)]
 struct ConfigSchema {
-    keys_activate: OneOrManySchema<KeySchema>,
-    keys_app_activate: Option<OneOrManySchema<KeySchema>>,
+    hold_duration_ms: Option<u64>,
+    keys_activate: OneOrManySchema<ActivationKeySchema>,
+    keys_app_activate: Option<OneOrManySchema<ActivationKeySchema>>,
     keys_back: Option<OneOrManySchema<KeySchema>>,
     keys_deactivate: Option<OneOrManySchema<KeySchema>>,
+    options_per_page: Option<usize>,
     layers: Option<Vec<LayerSchema>>,
-    commands: Option<Vec<CommandSchema>>,
+    commands: Option<Vec<ShortcutCommandSchema>>,
     #[serde(default)]
     app_specific_layers: Vec<ApplicationConfigSchema>,
 }
@@ -42,30 +57,49 @@ struct ApplicationConfigSchema {
     #[serde(rename = "match")]
     pattern: String,
     layers: Option<Vec<LayerSchema>>,
-    commands: Option<Vec<CommandSchema>>,
+    commands: Option<Vec<ShortcutCommandSchema>>,
 }
 
 impl TryFrom<ConfigSchema> for Config {
     type Error = String;
 
     fn try_from(value: ConfigSchema) -> Result<Self, Self::Error> {
-        let keys_activate: Vec<Key> = value.keys_activate.try_into()?;
+        let keys_activate = parse_activation_keys(value.keys_activate)?;
 
-        let keys_app_activate: Vec<Key> = match value.keys_app_activate {
+        let keys_app_activate = match value.keys_app_activate {
             None => vec![],
-            Some(keys) => keys.try_into()?,
+            Some(keys) => parse_activation_keys(keys)?,
         };
 
         let keys_back: Vec<Key> = match value.keys_back {
             None => vec![],
-            Some(keys) => keys.try_into()?,
+            Some(keys) => parse_layout_keys("keys_back", keys)?,
         };
 
         let keys_deactivate: Vec<Key> = match value.keys_deactivate {
-            None => keys_activate.clone(),
-            Some(keys) => keys.try_into()?,
+            None => keys_activate
+                .iter()
+                .map(|activation| {
+                    let mut key = activation.key.clone();
+                    key.set_physical(false);
+                    key
+                })
+                .collect(),
+            Some(keys) => parse_layout_keys("keys_deactivate", keys)?,
         };
 
+        // Every option on a page must be guaranteed a mnemonic, which caps the page size.
+        let options_per_page = value.options_per_page.unwrap_or(GUARANTEED_MNEMONIC_COUNT);
+        if !(1..=GUARANTEED_MNEMONIC_COUNT).contains(&options_per_page) {
+            return Err(format!(
+                "options_per_page must be between 1 and {GUARANTEED_MNEMONIC_COUNT}"
+            ));
+        }
+
+        let hold_duration = value
+            .hold_duration_ms
+            .map_or(DEFAULT_HOLD_DURATION, Duration::from_millis);
+
         let root_layer = try_parse_layer("Root".to_string(), value.layers, value.commands)?;
 
         if !value.app_specific_layers.is_empty() && keys_app_activate.is_empty() {
@@ -91,6 +125,8 @@ impl TryFrom<ConfigSchema> for Config {
             keys_app_activate,
             keys_back,
             keys_deactivate,
+            options_per_page,
+            hold_duration,
             root_layer,
             app_specific_layers,
         })
@@ -100,25 +136,55 @@ impl TryFrom<ConfigSchema> for Config {
 #[derive(Debug, Deserialize)]
 struct LayerSchema {
     layers: Option<Vec<LayerSchema>>,
-    commands: Option<Vec<CommandSchema>>,
-    shortcut: KeySchema,
+    commands: Option<Vec<ShortcutCommandSchema>>,
+    /// A single key, or a sequence of keys pressed one after another.
+    shortcut: OneOrManySchema<KeySchema>,
     name: String,
 }
 
-impl TryFrom<LayerSchema> for (Key, Layer) {
+impl TryFrom<LayerSchema> for (Vec<Key>, Layer) {
     type Error = String;
 
     fn try_from(value: LayerSchema) -> Result<Self, Self::Error> {
-        let shortcut: Key = value.shortcut.try_into()?;
+        let shortcut: Vec<Key> = value.shortcut.try_into()?;
+        if shortcut.is_empty() {
+            return Err("shortcut must have at least one key".into());
+        }
         let layer = try_parse_layer(value.name, value.layers, value.commands)?;
         Ok((shortcut, layer))
     }
 }
 
+#[derive(Debug, Deserialize)]
+struct ShortcutCommandSchema {
+    /// A single key, or a sequence of keys pressed one after another.
+    pub shortcut: OneOrManySchema<KeySchema>,
+    #[serde(flatten)]
+    pub command: CommandSchema,
+    /// Command run instead when the shortcut key is held down.
+    pub hold: Option<CommandSchema>,
+}
+
+impl TryFrom<ShortcutCommandSchema> for (Vec<Key>, Command) {
+    type Error = String;
+
+    fn try_from(value: ShortcutCommandSchema) -> Result<Self, Self::Error> {
+        let shortcut: Vec<Key> = value.shortcut.try_into()?;
+        if shortcut.is_empty() {
+            return Err("shortcut must have at least one key".into());
+        }
+
+        let mut command: Command = value.command.try_into()?;
+        if let Some(hold) = value.hold {
+            command.set_hold(hold.try_into()?);
+        }
+        Ok((shortcut, command))
+    }
+}
+
 #[derive(Debug, Deserialize)]
 struct CommandSchema {
     pub name: String,
-    pub shortcut: KeySchema,
     pub steps: Vec<StepSchema>,
     pub parameters: Option<OneOrManySchema<ParameterSchema>>,
     #[serde(rename = "final")]
@@ -130,12 +196,10 @@ struct CommandSchema {
     pub is_synchronous: bool,
 }
 
-impl TryFrom<CommandSchema> for (Key, Command) {
+impl TryFrom<CommandSchema> for Command {
     type Error = String;
 
     fn try_from(value: CommandSchema) -> Result<Self, Self::Error> {
-        let shortcut: Key = value.shortcut.try_into()?;
-
         let mut steps: Vec<EffectTemplate> = value
             .steps
             .into_iter()
@@ -160,10 +224,19 @@ impl TryFrom<CommandSchema> for (Key, Command) {
             CommandError::UnusedParameter(idx) => {
                 format!("declared {idx}. parameter is unused")
             }
+            CommandError::LaterParameterReferenced {
+                parameter,
+                referenced,
+            } => format!(
+                "command of {parameter}. parameter refers to {referenced}. parameter, which is not read before it"
+            ),
+            CommandError::UnknownParameterReferenced { parameter, name } => {
+                format!("command of {parameter}. parameter refers to unknown parameter {{{name}}}")
+            }
         })?;
 
         command.set_final(value.is_final);
-        Ok((shortcut, command))
+        Ok(command)
     }
 }
 
@@ -187,31 +260,183 @@ struct ParameterSchema {
     pub type_: String,
     pub options: Option<Vec<String>>,
     pub generate_options: Option<GenerateOptionsSchema>,
+    pub default_from: Option<String>,
+    pub history: Option<bool>,
+    pub pattern: Option<String>,
+    pub min_length: Option<usize>,
+    pub max_length: Option<usize>,
+    pub required: Option<bool>,
+    pub from_command: Option<String>,
+    pub complete: Option<String>,
+    pub root: Option<String>,
+    pub glob: Option<String>,
+    pub kind: Option<String>,
+    pub hidden: Option<bool>,
+    pub substitute: Option<String>,
+    #[serde(default)]
+    pub optional: bool,
+    pub default: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
 struct GenerateOptionsSchema {
-    pub command: String,
+    pub command: Option<String>,
+    pub file: Option<String>,
+    pub glob: Option<String>,
     pub split_by: Option<String>,
+    pub split_by_regex: Option<String>,
+    pub json: Option<JsonSelectorSchema>,
+    pub cache_ttl_ms: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonSelectorSchema {
+    #[serde(default)]
+    pub items: String,
+    pub label: String,
+    pub value: Option<String>,
+}
+
+impl TryFrom<GenerateOptionsSchema> for OptionSource {
+    type Error = String;
+
+    fn try_from(value: GenerateOptionsSchema) -> Result<Self, Self::Error> {
+        let format = match (value.split_by, value.split_by_regex, value.json) {
+            (None, None, None) => None,
+            (Some(separator), None, None) => {
+                Some(OutputFormat::Split(Separator::Literal(separator)))
+            }
+            (None, Some(pattern), None) => {
+                let pattern = regex::Regex::new(&pattern)
+                    .map_err(|e| format!("split_by_regex is not a valid regex: {e}"))?;
+                Some(OutputFormat::Split(Separator::Pattern(pattern)))
+            }
+            (None, None, Some(json)) => Some(OutputFormat::Json(JsonSelector::new(
+                &json.items,
+                &json.label,
+                json.value.as_deref(),
+            ))),
+            _ => return Err("only one of split_by, split_by_regex and json can be used".into()),
+        };
+        let format_or_lines = |format: Option<OutputFormat>| {
+            format.unwrap_or(OutputFormat::Split(Separator::Literal("\n".into())))
+        };
+
+        match (value.command, value.file, value.glob) {
+            (Some(command), None, None) => Ok(OptionSource::Command {
+                command: OptionsCommandTemplate::new(&command)?,
+                format: format_or_lines(format),
+            }),
+            (None, Some(path), None) => {
+                if value.cache_ttl_ms.is_some() {
+                    return Err("cache_ttl_ms can only be used with command".into());
+                }
+                Ok(OptionSource::File {
+                    path,
+                    format: format_or_lines(format),
+                })
+            }
+            (None, None, Some(pattern)) => {
+                if value.cache_ttl_ms.is_some() {
+                    return Err("cache_ttl_ms can only be used with command".into());
+                }
+                if format.is_some() {
+                    return Err("glob options cannot be split or parsed as json".into());
+                }
+                Ok(OptionSource::Glob { pattern })
+            }
+            _ => Err("exactly one of command, file and glob must be provided".into()),
+        }
+    }
 }
 
 impl TryFrom<ParameterSchema> for CommandParameter {
     type Error = String;
 
     fn try_from(value: ParameterSchema) -> Result<Self, Self::Error> {
+        check_type_specific_options(&value)?;
+
         let parameter_type = match value.type_.as_str() {
             "character" => ParameterDefinitionVariant::Character(CharacterParameter),
-            "text" => ParameterDefinitionVariant::Text(TextParameter),
+            "text" => {
+                let default_from = value
+                    .default_from
+                    .as_deref()
+                    .map(Selection::try_from)
+                    .transpose()?;
+                let pattern = value
+                    .pattern
+                    .as_deref()
+                    .map(regex::Regex::new)
+                    .transpose()
+                    .map_err(|e| format!("pattern is not a valid regex: {e}"))?;
+                if let (Some(min_length), Some(max_length)) = (value.min_length, value.max_length)
+                    && min_length > max_length
+                {
+                    return Err("min_length cannot be greater than max_length".into());
+                }
+
+                ParameterDefinitionVariant::Text(TextParameter {
+                    default_from,
+                    history: value.history.unwrap_or(true),
+                    pattern,
+                    min_length: value.min_length,
+                    max_length: value.max_length,
+                    required: value.required.unwrap_or(false),
+                    complete: value
+                        .complete
+                        .as_deref()
+                        .map(OptionsCommandTemplate::new)
+                        .transpose()?,
+                })
+            }
+            "path" => {
+                let defaults = PathParameter::default();
+                ParameterDefinitionVariant::Path(PathParameter {
+                    root: value.root.unwrap_or(defaults.root),
+                    glob: value.glob,
+                    kind: value
+                        .kind
+                        .as_deref()
+                        .map(PathKind::try_from)
+                        .transpose()?
+                        .unwrap_or(defaults.kind),
+                    hidden: value.hidden.unwrap_or(defaults.hidden),
+                })
+            }
+            "window" => {
+                // Windows are chosen like generated options, as the open windows change between
+                // the inputs.
+                let field = value
+                    .substitute
+                    .as_deref()
+                    .map(WindowField::try_from)
+                    .transpose()?
+                    .unwrap_or_default();
+                ParameterDefinitionVariant::Choose(ChooseParameter {
+                    options: Vec::new(),
+                    gen_options_source: Some(OptionSource::Windows { field }),
+                    gen_options_cache_ttl: None,
+                })
+            }
+            "secret" => {
+                let command = value
+                    .from_command
+                    .as_deref()
+                    .map(OptionsCommandTemplate::new)
+                    .transpose()?;
+                ParameterDefinitionVariant::Secret(SecretParameter { command })
+            }
             "choose" => {
-                let mut gen_options_command: Option<String> = None;
-                let mut gen_options_split_by: Option<String> = None;
+                let mut gen_options_source: Option<OptionSource> = None;
+                let mut gen_options_cache_ttl: Option<Duration> = None;
 
                 if let Some(dynamic_config) = value.generate_options {
-                    gen_options_command = Some(dynamic_config.command);
-                    gen_options_split_by = dynamic_config.split_by;
+                    gen_options_cache_ttl = dynamic_config.cache_ttl_ms.map(Duration::from_millis);
+                    gen_options_source = Some(dynamic_config.try_into()?);
                 }
 
-                match ChooseParameter::new(value.options, gen_options_command, gen_options_split_by)
+                match ChooseParameter::new(value.options, gen_options_source, gen_options_cache_ttl)
                 {
                     Ok(parameter) => ParameterDefinitionVariant::Choose(parameter),
                     Err(error) => {
@@ -223,32 +448,190 @@ impl TryFrom<ParameterSchema> for CommandParameter {
             otherwise => Err(format!("parameter type {otherwise} is unsupported"))?,
         };
 
-        Ok(CommandParameter::new(value.name, parameter_type))
+        let parameter = CommandParameter::new(value.name, parameter_type);
+        set_skip_value(parameter, value.optional, value.default)
+    }
+}
+
+/// Makes the parameter optional, leaving it to the default when skipped.
+fn set_skip_value(
+    mut parameter: CommandParameter,
+    optional: bool,
+    default: Option<String>,
+) -> Result<CommandParameter, String> {
+    if let Some(default) = &default {
+        if !optional {
+            return Err("default can only be used with optional parameters".into());
+        }
+        parameter
+            .parameter
+            .check_value(default)
+            .map_err(|e| format!("default is not a valid value: {e}"))?;
+    }
+
+    parameter.optional = optional;
+    parameter.default = default;
+    if parameter.optional && parameter.skip_value().is_none() {
+        return Err(
+            "only a text parameter which is not required may be optional without a default".into(),
+        );
+    }
+    Ok(parameter)
+}
+
+/// Parses the activation mode along with the timings it takes.
+fn parse_activation(
+    activation: Option<&str>,
+    tap_duration_ms: Option<u64>,
+    double_tap_interval_ms: Option<u64>,
+) -> Result<Activation, String> {
+    let max_duration = tap_duration_ms.map_or(DEFAULT_TAP_DURATION, Duration::from_millis);
+    match activation {
+        None | Some("press") if tap_duration_ms.is_some() => {
+            Err("tap_duration_ms can only be used with tap or double_tap activation".into())
+        }
+        _ if double_tap_interval_ms.is_some() && activation != Some("double_tap") => {
+            Err("double_tap_interval_ms can only be used with double_tap activation".into())
+        }
+        None | Some("press") => Ok(Activation::Press),
+        Some("tap") => Ok(Activation::Tap { max_duration }),
+        Some("double_tap") => Ok(Activation::DoubleTap {
+            max_duration,
+            max_interval: double_tap_interval_ms
+                .map_or(DEFAULT_DOUBLE_TAP_INTERVAL, Duration::from_millis),
+        }),
+        Some(otherwise) => Err(format!("activation {otherwise} is unsupported")),
+    }
+}
+
+/// Checks that the options specific to a parameter type are only used with that type.
+fn check_type_specific_options(value: &ParameterSchema) -> Result<(), String> {
+    let text_options = [
+        ("default_from", value.default_from.is_some()),
+        ("history", value.history.is_some()),
+        ("pattern", value.pattern.is_some()),
+        ("min_length", value.min_length.is_some()),
+        ("max_length", value.max_length.is_some()),
+        ("required", value.required.is_some()),
+        ("complete", value.complete.is_some()),
+    ];
+    if value.type_ != "text"
+        && let Some((option, _)) = text_options.iter().find(|(_, is_set)| *is_set)
+    {
+        return Err(format!("{option} can only be used with text parameters"));
+    }
+    let path_options = [
+        ("root", value.root.is_some()),
+        ("glob", value.glob.is_some()),
+        ("kind", value.kind.is_some()),
+        ("hidden", value.hidden.is_some()),
+    ];
+    if value.type_ != "path"
+        && let Some((option, _)) = path_options.iter().find(|(_, is_set)| *is_set)
+    {
+        return Err(format!("{option} can only be used with path parameters"));
+    }
+    if value.substitute.is_some() && value.type_ != "window" {
+        return Err("substitute can only be used with window parameters".into());
+    }
+    if value.from_command.is_some() && value.type_ != "secret" {
+        return Err("from_command can only be used with secret parameters".into());
+    }
+    Ok(())
+}
+
+/// An activation key pressed to activate, or keys sharing another activation.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum ActivationKeySchema {
+    Key(KeySchema),
+    KeysWithActivation {
+        keys: OneOrManySchema<KeySchema>,
+        activation: Option<String>,
+        tap_duration_ms: Option<u64>,
+        double_tap_interval_ms: Option<u64>,
+    },
+}
+
+impl TryFrom<ActivationKeySchema> for Vec<ActivationKey> {
+    type Error = String;
+
+    fn try_from(value: ActivationKeySchema) -> Result<Self, Self::Error> {
+        let (keys, activation) = match value {
+            ActivationKeySchema::Key(key) => (vec![key.try_into()?], Activation::Press),
+            ActivationKeySchema::KeysWithActivation {
+                keys,
+                activation,
+                tap_duration_ms,
+                double_tap_interval_ms,
+            } => (
+                keys.try_into()?,
+                parse_activation(
+                    activation.as_deref(),
+                    tap_duration_ms,
+                    double_tap_interval_ms,
+                )?,
+            ),
+        };
+
+        Ok(keys
+            .into_iter()
+            .map(|key| ActivationKey { key, activation })
+            .collect())
     }
 }
 
+/// Parses the activation keys, the entries of which may each stand for several keys.
+fn parse_activation_keys(
+    value: OneOrManySchema<ActivationKeySchema>,
+) -> Result<Vec<ActivationKey>, String> {
+    let entries: Vec<Vec<ActivationKey>> = value.try_into()?;
+    Ok(entries.into_iter().flatten().collect())
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(untagged)]
 enum KeySchema {
     Key(SymbolSchema),
     KeyAndModifiers {
         key: SymbolSchema,
-        modifiers: OneOrManySchema<ModifierSchema>,
+        modifiers: Option<OneOrManySchema<ModifierSchema>>,
+        /// Whether the key follows its place on the keyboard rather than the active layout.
+        #[serde(default)]
+        physical: bool,
     },
 }
 
+/// Parses the keys of the `field`, which follow the active keyboard layout only.
+fn parse_layout_keys(field: &str, value: OneOrManySchema<KeySchema>) -> Result<Vec<Key>, String> {
+    let keys: Vec<Key> = value.try_into()?;
+    if keys.iter().any(|key| key.physical) {
+        return Err(format!("{field} does not support physical keys"));
+    }
+    Ok(keys)
+}
+
 impl TryFrom<KeySchema> for Key {
     type Error = String;
 
     fn try_from(value: KeySchema) -> Result<Self, Self::Error> {
         match value {
             KeySchema::Key(key) => key.try_into().map(Key::new),
-            KeySchema::KeyAndModifiers { key, modifiers } => {
+            KeySchema::KeyAndModifiers {
+                key,
+                modifiers,
+                physical,
+            } => {
                 let mut symbol = key.try_into().map(Key::new)?;
+                let modifiers: Vec<Modifier> = match modifiers {
+                    None => vec![],
+                    Some(modifiers) => modifiers.try_into()?,
+                };
 
-                for modifier in TryInto::<Vec<Modifier>>::try_into(modifiers)? {
+                for modifier in modifiers {
                     symbol.add_modifier(modifier);
                 }
+                symbol.set_physical(physical);
 
                 Ok(symbol)
             }
@@ -312,16 +695,16 @@ fn default_true() -> bool {
 fn try_parse_layer(
     name: String,
     layers: Option<Vec<LayerSchema>>,
-    commands: Option<Vec<CommandSchema>>,
+    commands: Option<Vec<ShortcutCommandSchema>>,
 ) -> Result<Layer, String> {
     let mut layer = Layer::new(name);
 
     if let Some(schemas) = layers {
         for schema in schemas {
-            let (shortcut, sublayer): (Key, Layer) = schema.try_into()?;
-            if let Err((conflicting_key, _)) = layer.add_layer(shortcut, sublayer) {
+            let (shortcut, sublayer): (Vec<Key>, Layer) = schema.try_into()?;
+            if let Err((conflicting_keys, _)) = layer.add_layer(shortcut, sublayer) {
                 let error_message = format!(
-                    "Can not assign layer to key {conflicting_key:?} because of an existing binding!"
+                    "Can not assign layer to keys {conflicting_keys:?} because of an existing binding sharing its keys!"
                 );
                 return Err(error_message);
             }
@@ -330,10 +713,10 @@ fn try_parse_layer(
 
     if let Some(schemas) = commands {
         for schema in schemas {
-            let (shortcut, command): (Key, Command) = schema.try_into()?;
-            if let Err((conflicting_key, _)) = layer.add_command(shortcut, command) {
+            let (shortcut, command): (Vec<Key>, Command) = schema.try_into()?;
+            if let Err((conflicting_keys, _)) = layer.add_command(shortcut, command) {
                 let error_message = format!(
-                    "Could not assign command to key {conflicting_key:?} because of an existing binding!"
+                    "Could not assign command to keys {conflicting_keys:?} because of an existing binding sharing its keys!"
                 );
                 return Err(error_message);
             }
@@ -59,12 +59,16 @@ fn read_parameter_values(
     command: &Command,
     layers: &[&Layer],
 ) -> Result<Vec<ParameterValueVariant>, CommandExecutionResult> {
-    let p_input_context = ParameterInputContext { command, layers };
-
     let mut values: Vec<ParameterValueVariant> = vec![];
 
     // Parameters values are read one by one into a vector using the parameter input mode..
     for parameter in command.get_parameters() {
+        let p_input_context = ParameterInputContext {
+            command,
+            layers,
+            previous_values: &values,
+            skip_value: parameter.skip_value(),
+        };
         let parameter_value = match run_parameter_input_mode(ctx, &p_input_context, parameter) {
             ParameterInputResult::Ok(value) => value,
             ParameterInputResult::Cancel => {
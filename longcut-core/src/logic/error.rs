@@ -1,6 +1,11 @@
 use super::Context;
 use crate::port::executor::ExecutorError;
+use crate::port::input::{InputError, KeyCapture};
 use crate::port::view::{ErrorViewModel, ViewAction, ViewModel};
+use std::time::Duration;
+
+/// How long the input error is shown for, unless it is dismissed earlier.
+const INPUT_ERROR_DURATION: Duration = Duration::from_secs(3);
 
 pub enum ErrorResult {
     Abort,
@@ -12,7 +17,13 @@ pub enum ErrorResult {
 pub fn run_error_mode(ctx: &Context, error: &ExecutorError) -> ErrorResult {
     render(ctx, error);
     loop {
-        let press = ctx.input.capture_any();
+        let press = match ctx.input.capture_any() {
+            Ok(press) => press,
+            Err(error) => {
+                run_input_error_mode(ctx, &error);
+                return ErrorResult::Abort;
+            }
+        };
         if ctx.keys_deactivate.contains(&press) {
             return ErrorResult::Abort;
         } else if ctx.keys_back.contains(&press) {
@@ -23,18 +34,54 @@ pub fn run_error_mode(ctx: &Context, error: &ExecutorError) -> ErrorResult {
     }
 }
 
-fn render(ctx: &Context, error: &ExecutorError) {
-    let error_type = match error {
-        ExecutorError::RuntimeError(_) => "Runtime error",
-        ExecutorError::StartupError => "Startup error",
-        ExecutorError::UnknownError => "Unknown error",
+/// Informs of the input error for a while, or until one of the deactivating keys is pressed. The
+/// program deactivates afterwards, as it cannot be operated without the input.
+pub fn run_input_error_mode(ctx: &Context, error: &InputError) {
+    let error_details = match error {
+        InputError::GrabFailed => {
+            "The keyboard is held by another application, such as a screen locker"
+        }
+        // Nothing can be shown without the display server either.
+        InputError::Disconnected => return,
     };
+    let actions: Vec<_> = ctx
+        .keys_deactivate
+        .iter()
+        .map(|key| (key, ViewAction::Deactivate))
+        .collect();
+    ctx.view.render(ViewModel::Error(ErrorViewModel {
+        error_type: "Input unavailable",
+        error_details,
+        actions: &actions,
+    }));
 
-    let error_details = match error {
+    // The single keys can still be grabbed while another application holds the whole keyboard.
+    let keys: Vec<KeyCapture> = ctx
+        .keys_deactivate
+        .iter()
+        .cloned()
+        .map(KeyCapture::Press)
+        .collect();
+    let _ = ctx.input.capture_one_timeout(&keys, INPUT_ERROR_DURATION);
+    ctx.view.render(ViewModel::None);
+}
+
+/// Describes the error in a form suitable for displaying to the user.
+pub fn error_details(error: &ExecutorError) -> &str {
+    match error {
         ExecutorError::RuntimeError(details) => details.trim(),
         ExecutorError::StartupError => "Failed to start the target command",
         ExecutorError::UnknownError => "No error details available",
+    }
+}
+
+fn render(ctx: &Context, error: &ExecutorError) {
+    let error_type = match error {
+        ExecutorError::RuntimeError(_) => "Runtime error",
+        ExecutorError::StartupError => "Startup error",
+        ExecutorError::UnknownError => "Unknown error",
     };
+    let error_details = error_details(error);
 
     let mut actions = vec![];
 
@@ -1,10 +1,18 @@
 use super::Context;
+use super::error::run_input_error_mode;
 use super::layer_navigation::run_layer_navigation_mode;
 use crate::config::ApplicationConfig;
+use crate::model::activation::{Activation, ActivationKey, ActivationTracker};
+use crate::model::key::Key;
+use crate::port::input::{InputError, KeyCapture};
 use crate::port::view::{ErrorViewModel, ViewAction, ViewModel};
 
 /// Waits idly for the program activation signal and then moves to layer navigation.
-pub fn run_inactive_mode(ctx: &Context) {
+///
+/// # Errors
+///
+/// Returns an error if the input is lost while waiting.
+pub fn run_inactive_mode(ctx: &Context) -> Result<(), InputError> {
     let activation_keys: Vec<_> = ctx
         .keys_activate
         .iter()
@@ -12,14 +20,46 @@ pub fn run_inactive_mode(ctx: &Context) {
         .cloned()
         .collect();
 
-    let press = ctx.input.capture_one(&activation_keys);
-    if ctx.keys_activate.contains(&press) {
+    let press = capture_activation(ctx, &activation_keys)?;
+    if ctx
+        .keys_activate
+        .iter()
+        .any(|activation| activation.key == press)
+    {
         run_layer_navigation_mode(ctx);
     } else {
         run_app_shortcut_mode(ctx, ctx.app_specific_layers);
     }
 
     ctx.view.render(ViewModel::None);
+    Ok(())
+}
+
+/// Waits until one of the keys activates the program, as told by its activation, and returns it.
+/// The second tap of a double tap is waited for only as long as its interval lasts.
+fn capture_activation(ctx: &Context, keys: &[ActivationKey]) -> Result<Key, InputError> {
+    let key_captures: Vec<KeyCapture> = keys.iter().map(to_key_capture).collect();
+    let mut tracker = ActivationTracker::new(keys);
+    loop {
+        let captured = match tracker.timeout() {
+            None => Some(ctx.input.capture_one(&key_captures)?),
+            Some(timeout) => ctx.input.capture_one_timeout(&key_captures, timeout)?,
+        };
+        if let Some(key) = tracker.capture(captured) {
+            return Ok(key);
+        }
+    }
+}
+
+/// The keys activating by a tap, once or twice, are captured by their taps.
+fn to_key_capture(activation_key: &ActivationKey) -> KeyCapture {
+    let key = activation_key.key.clone();
+    match activation_key.activation {
+        Activation::Press => KeyCapture::Press(key),
+        Activation::Tap { max_duration } | Activation::DoubleTap { max_duration, .. } => {
+            KeyCapture::Tap { key, max_duration }
+        }
+    }
 }
 
 fn run_app_shortcut_mode(ctx: &Context, app_layers: &[ApplicationConfig]) {
@@ -38,11 +78,19 @@ fn run_app_shortcut_mode(ctx: &Context, app_layers: &[ApplicationConfig]) {
         input: ctx.input,
         view: ctx.view,
         window_manager: ctx.window_manager,
+        storage: ctx.storage,
+        filesystem: ctx.filesystem,
+        clipboard: ctx.clipboard,
+        options_cache: ctx.options_cache,
         keys_activate: ctx.keys_app_activate,
         keys_app_activate: &[],
         keys_back: ctx.keys_back,
         keys_deactivate: ctx.keys_deactivate,
+        keys_refresh: ctx.keys_refresh,
         keys_retry: ctx.keys_retry,
+        keys_skip: ctx.keys_skip,
+        options_per_page: ctx.options_per_page,
+        hold_duration: ctx.hold_duration,
         root_layer: &app_config.root_layer,
         app_specific_layers: &[],
     });
@@ -69,7 +117,13 @@ fn show_app_not_configured_error(ctx: &Context, window_name: &str) {
         actions: &actions,
     }));
     loop {
-        let press = ctx.input.capture_any();
+        let press = match ctx.input.capture_any() {
+            Ok(press) => press,
+            Err(error) => {
+                run_input_error_mode(ctx, &error);
+                break;
+            }
+        };
         if ctx.keys_deactivate.contains(&press) || ctx.keys_back.contains(&press) {
             break;
         }
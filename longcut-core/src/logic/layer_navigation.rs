@@ -1,68 +1,111 @@
 use super::Context;
 use super::command_execution::{CommandExecutionResult, run_command_execution_mode};
+use super::error::run_input_error_mode;
 use crate::model::key::Key;
 use crate::model::layer::{Action, Layer};
+use crate::model::shortcut_map::ShortcutMap;
 use crate::port::view::{LayerNavigationViewModel, ViewAction, ViewModel};
 
 /// Enables the user to navigate through the layer tree.
 pub fn run_layer_navigation_mode(ctx: &Context) {
     let mut layers = vec![ctx.root_layer];
+    // Earlier keys of a multi-key shortcut pressed so far.
+    let mut pressed: Vec<Key> = vec![];
     loop {
         let active_layer = layers.last().unwrap();
         let is_branched = layers.len() > 1;
 
         // Rendering
-        if is_branched {
-            render_branch(ctx, layers.as_slice());
+        if is_branched || !pressed.is_empty() {
+            render_branch(ctx, layers.as_slice(), &pressed);
         } else {
             render_root(ctx, active_layer);
         }
 
         // Input handling
-        let press = ctx.input.capture_any();
+        let press = match ctx.input.capture_any() {
+            Ok(press) => press,
+            Err(error) => {
+                run_input_error_mode(ctx, &error);
+                return;
+            }
+        };
         if ctx.keys_deactivate.contains(&press) {
             return;
         }
 
+        // Backing out abandons a partially pressed shortcut before leaving the layer.
+        if !pressed.is_empty() && ctx.keys_back.contains(&press) {
+            pressed.clear();
+            continue;
+        }
+
         if is_branched && ctx.keys_back.contains(&press) {
             layers.pop();
             continue;
         }
 
-        if let Some(action) = active_layer.resolve_shortcut(&press) {
-            match action {
-                Action::Branch(into) => {
-                    layers.push(into);
-                }
-                Action::Execute(command) => {
-                    match run_command_execution_mode(ctx, command, &layers) {
-                        CommandExecutionResult::KeepGoing => {
-                            // Do nothing.
-                        }
-
-                        CommandExecutionResult::Finished => {
-                            return;
-                        }
+        // The keys of physical shortcuts are matched by their place on the keyboard first.
+        let physical_action = ctx.input.physical_key().and_then(|physical| {
+            let action = active_layer.resolve_shortcut(&pressed, &physical)?;
+            Some((physical, action))
+        });
+        let (press, action) = if let Some((physical, action)) = physical_action {
+            (physical, Some(action))
+        } else {
+            let action = active_layer.resolve_shortcut(&pressed, &press);
+            (press, action)
+        };
+        if let Some(Action::Sequence(_)) = action {
+            pressed.push(press);
+            continue;
+        }
+
+        // A key which does not continue the shortcut abandons it as well.
+        pressed.clear();
+        match action {
+            Some(Action::Branch(into)) => {
+                layers.push(into);
+            }
+            Some(Action::Execute(command)) => {
+                // Telling a hold apart from a press takes waiting for the key to be released.
+                let command = match command.get_hold() {
+                    Some(hold) if !ctx.input.await_release(ctx.hold_duration) => hold,
+                    _ => command,
+                };
+                match run_command_execution_mode(ctx, command, &layers) {
+                    CommandExecutionResult::KeepGoing => {
+                        // Do nothing.
+                    }
+
+                    CommandExecutionResult::Finished => {
+                        return;
                     }
                 }
             }
+            Some(Action::Sequence(_)) | None => {}
         }
     }
 }
 
 fn render_root(ctx: &Context, layer: &Layer) {
-    let mut actions = render_layer_actions(layer);
+    let mut actions = render_layer_actions(&layer.shortcuts);
 
     // Deactivate is always available.
     for key in ctx.keys_deactivate {
         actions.push((key, ViewAction::Deactivate));
     }
 
-    render_navigation_view(ctx, &actions, &[layer]);
+    render_navigation_view(ctx, &actions, &[layer], &[]);
 }
 
-fn render_branch(ctx: &Context, layers: &[&Layer]) {
-    let mut actions = render_layer_actions(layers.last().unwrap());
+fn render_branch(ctx: &Context, layers: &[&Layer], pressed: &[Key]) {
+    // Only the shortcuts continuing the pressed keys are available.
+    let layer = layers.last().unwrap();
+    let mut actions = layer
+        .continuations(pressed)
+        .map(render_layer_actions)
+        .unwrap_or_default();
 
     // Back keys are available.
     for key in ctx.keys_back {
@@ -74,26 +117,33 @@ fn render_branch(ctx: &Context, layers: &[&Layer]) {
         actions.push((key, ViewAction::Deactivate));
     }
 
-    render_navigation_view(ctx, &actions, layers);
+    render_navigation_view(ctx, &actions, layers, pressed);
 }
 
-fn render_navigation_view(ctx: &Context, actions: &[(&Key, ViewAction)], layers: &[&Layer]) {
+fn render_navigation_view(
+    ctx: &Context,
+    actions: &[(&Key, ViewAction)],
+    layers: &[&Layer],
+    pressed: &[Key],
+) {
     let model = LayerNavigationViewModel {
         actions,
         layer_stack: layers,
+        pressed_keys: pressed,
     };
 
     ctx.view.render(ViewModel::LayerNavigation(model));
 }
 
-fn render_layer_actions(layer: &Layer) -> Vec<(&Key, ViewAction)> {
+fn render_layer_actions(shortcuts: &ShortcutMap<Action>) -> Vec<(&Key, ViewAction)> {
     let mut actions = vec![];
 
     // Collecting all layer actions into the view action vector.
-    for (press, action) in &*layer.shortcuts {
+    for (press, action) in &**shortcuts {
         let view_action = match action {
             Action::Branch(layer) => ViewAction::Branch(layer.name.clone()),
             Action::Execute(command) => ViewAction::Execute(command.name.clone()),
+            Action::Sequence(continuations) => ViewAction::Sequence(sequence_names(continuations)),
         };
 
         actions.push((press, view_action));
@@ -101,3 +151,15 @@ fn render_layer_actions(layer: &Layer) -> Vec<(&Key, ViewAction)> {
 
     actions
 }
+
+/// Names of the layers and commands the shortcuts lead to, however many keys they take.
+fn sequence_names(shortcuts: &ShortcutMap<Action>) -> Vec<String> {
+    shortcuts
+        .values()
+        .flat_map(|action| match action {
+            Action::Branch(layer) => vec![layer.name.clone()],
+            Action::Execute(command) => vec![command.name.clone()],
+            Action::Sequence(continuations) => sequence_names(continuations),
+        })
+        .collect()
+}
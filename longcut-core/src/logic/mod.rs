@@ -9,12 +9,20 @@ mod inactive;
 mod layer_navigation;
 mod parameter_input;
 
+use std::cell::RefCell;
+use std::time::Duration;
+
 use crate::config::ApplicationConfig;
+use crate::model::activation::ActivationKey;
 use crate::model::key::Key;
 use crate::model::layer::Layer;
+use crate::model::timed_cache::TimedCache;
 use crate::port::WindowManager;
+use crate::port::clipboard::Clipboard;
 use crate::port::executor::Executor;
+use crate::port::filesystem::Filesystem;
 use crate::port::input::Input;
+use crate::port::storage::Storage;
 use crate::port::view::View;
 
 pub use inactive::run_inactive_mode;
@@ -25,13 +33,24 @@ pub struct Context<'a> {
     pub input: &'a dyn Input,
     pub view: &'a dyn View,
     pub window_manager: &'a dyn WindowManager,
+    pub storage: &'a dyn Storage,
+    pub filesystem: &'a dyn Filesystem,
+    pub clipboard: &'a dyn Clipboard,
+
+    // State
+    /// Outputs of the option generation commands, by command.
+    pub options_cache: &'a RefCell<TimedCache<String>>,
 
     // Configuration
-    pub keys_activate: &'a [Key],
-    pub keys_app_activate: &'a [Key],
+    pub keys_activate: &'a [ActivationKey],
+    pub keys_app_activate: &'a [ActivationKey],
     pub keys_back: &'a [Key],
     pub keys_deactivate: &'a [Key],
+    pub keys_refresh: &'a [Key],
     pub keys_retry: &'a [Key],
+    pub keys_skip: &'a [Key],
+    pub options_per_page: usize,
+    pub hold_duration: Duration,
 
     // Layer
     pub root_layer: &'a Layer,
@@ -1,16 +1,35 @@
 use itertools::Itertools;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::mpsc::{Receiver, TryRecvError};
+use std::time::{Duration, Instant};
 
 use super::Context;
+use super::error::{error_details, run_input_error_mode};
 use crate::model::command::{Command, CommandParameter};
-use crate::model::key::{Key, Symbol};
+use crate::model::completion::Completion;
+use crate::model::input_history::InputHistory;
+use crate::model::key::{Key, Modifier, Symbol};
 use crate::model::layer::Layer;
+use crate::model::option_list::OptionList;
+use crate::model::option_source::{ChooseOption, OptionSource, path_options, window_options};
+use crate::model::options_command::OptionsCommandTemplate;
 use crate::model::parameter::{
     CharacterParameter, ChooseParameter, Parameter, ParameterDefinitionVariant,
-    ParameterValueVariant, TextParameter,
+    ParameterValueVariant, PathParameter, SecretParameter, TextParameter,
 };
+use crate::model::selection::Selection;
 use crate::model::shortcut_map::ShortcutMap;
+use crate::model::text_buffer::TextBuffer;
+use crate::port::executor::ExecutorError;
+use crate::port::filesystem::{DirectoryEntry, FilesystemError};
+use crate::port::input::TextEvent;
+use crate::port::storage::StorageError;
 use crate::port::view;
-use crate::port::view::{ParameterInputViewModel, ViewModel};
+use crate::port::view::{OptionGenerationStatus, ParameterInputViewModel, ViewAction, ViewModel};
+
+/// How often the generation of choose parameter options is checked for completion.
+const GENERATION_POLL_INTERVAL: Duration = Duration::from_millis(50);
 
 pub enum ParameterInputResult {
     Ok(ParameterValueVariant),
@@ -18,9 +37,25 @@ pub enum ParameterInputResult {
     Exit,
 }
 
+/// State of generating the options of a choose parameter in the background.
+enum Generation {
+    /// The parameter has pre-configured options only.
+    Static,
+    Finished,
+    Running {
+        command: String,
+        receiver: Receiver<Result<String, ExecutorError>>,
+    },
+    Failed(String),
+}
+
 pub struct ParameterInputContext<'a> {
     pub command: &'a Command,
     pub layers: &'a [&'a Layer],
+    /// Values of the parameters read before the current one.
+    pub previous_values: &'a [ParameterValueVariant],
+    /// Value of the parameter when its input is skipped, or nothing if it cannot be skipped.
+    pub skip_value: Option<&'a str>,
 }
 
 /// Processes input from the user to generate values for command parameters.
@@ -36,31 +71,72 @@ pub fn run_parameter_input_mode(
         ParameterDefinitionVariant::Choose(definition) => {
             read_choose_parameter(ctx, context, &parameter.name, definition)
         }
+        ParameterDefinitionVariant::Path(definition) => {
+            read_path_parameter(ctx, context, &parameter.name, definition)
+        }
+        ParameterDefinitionVariant::Secret(definition) => {
+            read_secret_parameter(ctx, context, &parameter.name, definition)
+        }
         ParameterDefinitionVariant::Text(definition) => {
             read_text_parameter(ctx, context, &parameter.name, definition)
         }
     }
 }
 
+/// Skips the input if the parameter is optional and the key is a skip key.
+fn try_skip(
+    ctx: &Context,
+    context: &ParameterInputContext,
+    press: &Key,
+) -> Option<ParameterInputResult> {
+    let value = context.skip_value?;
+    ctx.keys_skip
+        .contains(press)
+        .then(|| ParameterInputResult::Ok(ParameterValueVariant::Default(value.to_string())))
+}
+
+fn skip_actions<'a>(
+    ctx: &'a Context,
+    context: &ParameterInputContext,
+) -> Vec<(&'a Key, ViewAction)> {
+    match (context.skip_value, ctx.keys_skip.first()) {
+        (Some(value), Some(key)) => vec![(key, ViewAction::Skip(value.to_string()))],
+        _ => Vec::new(),
+    }
+}
+
 fn read_character_parameter(
     ctx: &Context,
     context: &ParameterInputContext,
     parameter_name: &str,
     parameter: &CharacterParameter,
 ) -> ParameterInputResult {
+    let parameter_actions = skip_actions(ctx, context);
     let view_model = ParameterInputViewModel {
         command: context.command,
         parameter_name,
         parameter: view::ParameterVariant::CharInput,
         layer_stack: context.layers,
+        actions: &parameter_actions,
     };
     ctx.view.render(ViewModel::ParameterInput(view_model));
 
-    for press in ctx.input.capture_any_iter() {
+    let presses = match ctx.input.capture_any_iter() {
+        Ok(presses) => presses,
+        Err(error) => {
+            run_input_error_mode(ctx, &error);
+            return ParameterInputResult::Exit;
+        }
+    };
+    for press in presses {
         if ctx.keys_deactivate.contains(&press) {
             return ParameterInputResult::Exit;
         }
 
+        if let Some(result) = try_skip(ctx, context, &press) {
+            return result;
+        }
+
         if ctx.keys_back.contains(&press) {
             return ParameterInputResult::Cancel;
         }
@@ -84,135 +160,1077 @@ fn read_choose_parameter(
     parameter_name: &str,
     parameter: &ChooseParameter,
 ) -> ParameterInputResult {
-    let generated_parameter_options: Vec<String> =
-        if let Some(gen_command) = &parameter.gen_options_command {
-            if let Ok(output) = ctx.executor.run_to_completion(gen_command) {
-                output
-                    .split(&parameter.gen_options_split_by)
-                    .map(str::trim)
-                    .filter(|line| !line.is_empty())
-                    .map(std::string::ToString::to_string)
-                    .collect()
-            } else {
-                vec![]
+    // Pre-configured options are available right away, while the generated options are appended
+    // once the generation command finishes.
+    let unique_options: Vec<ChooseOption> = parameter.options.iter().unique().cloned().collect();
+    let mut options = OptionList::new(unique_options, ctx.options_per_page);
+    let mut generation = start_generation(ctx, context, parameter, &mut options, true);
+
+    // Mnemonics assigned during the earlier inputs of the same parameter are preferred, so that
+    // the options keep their shortcuts even when the generated options change order.
+    // Without the earlier mnemonics, the options are assigned new ones.
+    let scope = parameter_scope(context, parameter_name);
+    let stored_mnemonics = ctx.storage.load_mnemonics(&scope).unwrap_or_else(|error| {
+        log_storage_error("load the mnemonics", &error);
+        vec![]
+    });
+    let preferred: BTreeMap<String, Key> = stored_mnemonics
+        .into_iter()
+        .map(|(c, value)| (value, Key::new(Symbol::Character(c))))
+        .collect();
+
+    let mut is_filtering = false;
+    render_choose_parameter(
+        ctx,
+        context,
+        parameter_name,
+        &options,
+        &preferred,
+        is_filtering,
+        &generation,
+    );
+
+    let presses = match ctx.input.capture_any_timed_iter(GENERATION_POLL_INTERVAL) {
+        Ok(presses) => presses,
+        Err(error) => {
+            run_input_error_mode(ctx, &error);
+            return ParameterInputResult::Exit;
+        }
+    };
+    for press in presses {
+        let generation_changed = poll_generation(ctx, &mut generation, parameter, &mut options);
+        let Some(press) = press else {
+            if generation_changed {
+                render_choose_parameter(
+                    ctx,
+                    context,
+                    parameter_name,
+                    &options,
+                    &preferred,
+                    is_filtering,
+                    &generation,
+                );
             }
-        } else {
-            vec![]
+            continue;
         };
 
-    // Pre-configured and generated options are joined up into the same vector of mnemonic
-    // pairs. The pre-configured options come first in the vector so they get mapped first,
-    // preserving their shortcut mapping regardless of what output the generation command
-    // produces.
-    let preconfigured_options_iter = parameter.options.iter();
-    let generated_options_iter = generated_parameter_options.iter();
-    let unique_mnemonics: Vec<(&str, &String)> = preconfigured_options_iter
-        .chain(generated_options_iter)
+        if ctx.keys_deactivate.contains(&press) {
+            return ParameterInputResult::Exit;
+        }
+
+        if let Some(result) = try_skip(ctx, context, &press) {
+            return result;
+        }
+
+        if is_filtering {
+            // While filtering, the input is directed to the filter instead of the shortcuts.
+            match press.symbol {
+                Symbol::Character(c) => options.push_filter(c),
+                // Erasing past the beginning of the filter stops filtering.
+                Symbol::BackSpace => is_filtering = options.pop_filter().is_some(),
+                Symbol::Return | Symbol::Tab => {
+                    is_filtering = false;
+
+                    // A filter which leaves only one option is as good as choosing it.
+                    if let [option] = options.current_page().as_slice() {
+                        store_mnemonics(ctx, &scope, &options, &preferred);
+                        return choose_option(parameter, option);
+                    }
+                }
+                _ => { /* Irrelevant input. */ }
+            }
+        } else if ctx.keys_back.contains(&press) {
+            // Backing out clears an applied filter before cancelling the input altogether.
+            if options.filter().is_empty() {
+                return ParameterInputResult::Cancel;
+            }
+            options.clear_filter();
+        } else if matches!(generation, Generation::Failed(_)) && ctx.keys_retry.contains(&press) {
+            generation = start_generation(ctx, context, parameter, &mut options, true);
+        } else if can_refresh(&generation) && ctx.keys_refresh.contains(&press) {
+            generation = start_generation(ctx, context, parameter, &mut options, false);
+        } else if press.symbol == Symbol::Tab {
+            is_filtering = true;
+        } else if press.symbol == Symbol::PageDown {
+            options.next_page();
+        } else if press.symbol == Symbol::PageUp {
+            options.previous_page();
+        } else {
+            let shortcuts = assign_option_mnemonics(&options.current_page(), &preferred);
+            if let Some(option) = shortcuts.match_fuzzy(&press) {
+                store_mnemonics(ctx, &scope, &options, &preferred);
+                return choose_option(parameter, option);
+            }
+        }
+
+        // Re-render after each keystroke.
+        render_choose_parameter(
+            ctx,
+            context,
+            parameter_name,
+            &options,
+            &preferred,
+            is_filtering,
+            &generation,
+        );
+    }
+
+    // This is never reached unless the iterator unexpectedly ends.
+    ParameterInputResult::Exit
+}
+
+/// Starts generating the options. Commands are run in the background, and if the output of the
+/// command is cached and `use_cache` is set, the options are taken from the cache right away.
+/// Without `use_cache`, the cached output is dropped.
+/// Files, directories and windows are read right away.
+fn start_generation(
+    ctx: &Context,
+    context: &ParameterInputContext,
+    parameter: &ChooseParameter,
+    options: &mut OptionList<ChooseOption>,
+    use_cache: bool,
+) -> Generation {
+    let generated = match &parameter.gen_options_source {
+        None => return Generation::Static,
+        Some(OptionSource::Command { command, format }) => {
+            // The command may refer to the values of the earlier parameters.
+            let values: Vec<String> = context
+                .previous_values
+                .iter()
+                .map(ParameterValueVariant::to_substitution_string)
+                .collect();
+            let Ok(command) = command.render(&values) else {
+                return Generation::Failed(
+                    "Option generation refers to an unknown parameter".to_string(),
+                );
+            };
+
+            let mut cache = ctx.options_cache.borrow_mut();
+            if !use_cache {
+                // The refreshed output replaces the cached one, which is stale even if the
+                // refresh fails.
+                cache.remove(&command);
+            }
+            if let Some(output) = cache.get(&command, Instant::now()) {
+                format.parse(output)
+            } else {
+                let receiver = ctx.executor.run_to_completion_async(&command);
+                return Generation::Running { command, receiver };
+            }
+        }
+        Some(OptionSource::File { path, format }) => ctx
+            .filesystem
+            .read_to_string(path)
+            .map_err(|error| filesystem_error_details(&error))
+            .and_then(|contents| format.parse(&contents)),
+        Some(OptionSource::Glob { pattern }) => ctx
+            .filesystem
+            .glob(pattern)
+            .map(path_options)
+            .map_err(|error| filesystem_error_details(&error)),
+        Some(OptionSource::Windows { field }) => {
+            Ok(window_options(ctx.window_manager.list_windows(), *field))
+        }
+    };
+
+    match generated {
+        Ok(generated) => {
+            set_generated_options(parameter, options, generated);
+            Generation::Finished
+        }
+        Err(error) => Generation::Failed(error),
+    }
+}
+
+/// Checks whether the running generation has finished, adding the generated options to the list.
+/// Returns true if the state of the generation changed.
+fn poll_generation(
+    ctx: &Context,
+    generation: &mut Generation,
+    parameter: &ChooseParameter,
+    options: &mut OptionList<ChooseOption>,
+) -> bool {
+    let Generation::Running { command, receiver } = generation else {
+        return false;
+    };
+    let Some(OptionSource::Command { format, .. }) = &parameter.gen_options_source else {
+        return false;
+    };
+
+    *generation = match receiver.try_recv() {
+        Ok(Ok(output)) => match format.parse(&output) {
+            Ok(generated) => {
+                set_generated_options(parameter, options, generated);
+                if let Some(ttl) = parameter.gen_options_cache_ttl {
+                    let now = Instant::now();
+                    let mut cache = ctx.options_cache.borrow_mut();
+                    cache.insert(command.clone(), output, now, now + ttl);
+                }
+                Generation::Finished
+            }
+            Err(error) => Generation::Failed(error),
+        },
+        Ok(Err(error)) => Generation::Failed(error_details(&error).to_string()),
+        Err(TryRecvError::Empty) => return false,
+        Err(TryRecvError::Disconnected) => {
+            Generation::Failed(error_details(&ExecutorError::UnknownError).to_string())
+        }
+    };
+    true
+}
+
+/// Joins the pre-configured and generated options up into the same list. The pre-configured
+/// options come first in the list so they get mapped first, preserving their shortcut mapping
+/// regardless of what output the generation produces.
+fn set_generated_options(
+    parameter: &ChooseParameter,
+    options: &mut OptionList<ChooseOption>,
+    generated: Vec<ChooseOption>,
+) {
+    let unique_options = parameter
+        .options
+        .iter()
+        .cloned()
+        .chain(generated)
         .unique()
-        .map(|option| (option.as_str(), option))
         .collect();
+    options.set_options(unique_options);
+}
 
-    let mut shortcuts = ShortcutMap::<&String>::new();
-    shortcuts.auto_assign_mnemonics(unique_mnemonics);
+/// The input works without the stored mnemonics and history, so failing to load or store them
+/// is only logged.
+fn log_storage_error(action: &str, error: &StorageError) {
+    let details = match error {
+        StorageError::Unavailable => "No state directory",
+        StorageError::Io(details) | StorageError::Serialization(details) => details,
+    };
+    eprintln!("Failed to {action}: {details}");
+}
 
-    // The view is rendered based on the shortcut map content.
-    {
-        let values: Vec<(&Key, &str)> = shortcuts
-            .iter()
-            .map(|(key, value)| (key, value.as_str()))
-            .collect();
+fn filesystem_error_details(error: &FilesystemError) -> String {
+    match error {
+        FilesystemError::InvalidPattern(details) => format!("Invalid glob pattern: {details}"),
+        FilesystemError::Io(details) => details.clone(),
+    }
+}
 
-        let view_model = ParameterInputViewModel {
-            command: context.command,
-            parameter_name,
-            parameter: view::ParameterVariant::OptionInput { options: &values },
-            layer_stack: context.layers,
-        };
-        ctx.view.render(ViewModel::ParameterInput(view_model));
+/// Generated options can be refreshed whenever they are not already being generated.
+fn can_refresh(generation: &Generation) -> bool {
+    matches!(generation, Generation::Finished | Generation::Failed(_))
+}
+
+fn choose_option(parameter: &ChooseParameter, option: &ChooseOption) -> ParameterInputResult {
+    if let Ok(value) = parameter.try_assign_value(option.value.clone()) {
+        return ParameterInputResult::Ok(ParameterValueVariant::Choose(value));
+    }
+    // Invalid value silently ignored; stop regardless.
+    ParameterInputResult::Exit
+}
+
+/// Assigns mnemonic shortcuts for the options visible on a single page.
+fn assign_option_mnemonics<'a, T: AsRef<str>>(
+    page: &[&'a T],
+    preferred: &BTreeMap<String, Key>,
+) -> ShortcutMap<&'a T> {
+    let mnemonics: Vec<(&str, &T)> = page
+        .iter()
+        .map(|option| (option.as_ref(), *option))
+        .collect();
+
+    let mut shortcuts = ShortcutMap::<&T>::new();
+    shortcuts.auto_assign_mnemonics(mnemonics, preferred);
+    shortcuts
+}
+
+/// Persists the mnemonics of the visible page on top of the earlier ones. Mnemonics of options
+/// which no longer exist are forgotten, as are the earlier holders of the visible page's keys.
+fn store_mnemonics(
+    ctx: &Context,
+    scope: &str,
+    options: &OptionList<ChooseOption>,
+    preferred: &BTreeMap<String, Key>,
+) {
+    let mut mnemonics: BTreeMap<&str, char> = BTreeMap::new();
+    for (label, key) in preferred {
+        if let Symbol::Character(c) = key.symbol
+            && options
+                .options()
+                .iter()
+                .any(|option| &option.label == label)
+        {
+            mnemonics.insert(label, c);
+        }
+    }
+
+    let page = options.current_page();
+    for (key, option) in &*assign_option_mnemonics(&page, preferred) {
+        if let Symbol::Character(c) = key.symbol {
+            mnemonics.retain(|_, existing| *existing != c);
+            mnemonics.insert(&option.label, c);
+        }
     }
 
-    // With the view render out of the way, we read the input.
-    for press in ctx.input.capture_any_iter() {
+    let mnemonics: Vec<(char, String)> = mnemonics
+        .into_iter()
+        .map(|(label, c)| (c, label.to_string()))
+        .collect();
+    if let Err(error) = ctx.storage.store_mnemonics(scope, &mnemonics) {
+        log_storage_error("store the mnemonics", &error);
+    }
+}
+
+/// Identifies the parameter of a command by its position in the layer tree.
+fn parameter_scope(context: &ParameterInputContext, parameter_name: &str) -> String {
+    context
+        .layers
+        .iter()
+        .map(|layer| layer.name.as_str())
+        .chain([context.command.name.as_str(), parameter_name])
+        .join(" > ")
+}
+
+fn render_choose_parameter(
+    ctx: &Context,
+    context: &ParameterInputContext,
+    parameter_name: &str,
+    options: &OptionList<ChooseOption>,
+    preferred: &BTreeMap<String, Key>,
+    is_filtering: bool,
+    generation: &Generation,
+) {
+    let page = options.current_page();
+    let shortcuts = assign_option_mnemonics(&page, preferred);
+    let values: Vec<(&Key, &str)> = shortcuts
+        .iter()
+        .map(|(key, option)| (key, option.label.as_str()))
+        .collect();
+
+    let mut actions: Vec<(&Key, ViewAction)> = vec![];
+    if can_refresh(generation) {
+        for key in ctx.keys_refresh {
+            actions.push((key, ViewAction::Refresh));
+        }
+    }
+
+    let retry_actions: Vec<(&Key, ViewAction)> = ctx
+        .keys_retry
+        .iter()
+        .map(|key| (key, ViewAction::Retry))
+        .collect();
+    let generation = match generation {
+        Generation::Static | Generation::Finished => OptionGenerationStatus::Complete,
+        Generation::Running { .. } => OptionGenerationStatus::Loading,
+        Generation::Failed(error_details) => OptionGenerationStatus::Failed {
+            error_details,
+            actions: &retry_actions,
+        },
+    };
+
+    let parameter_actions = skip_actions(ctx, context);
+    let view_model = ParameterInputViewModel {
+        command: context.command,
+        parameter_name,
+        parameter: view::ParameterVariant::OptionInput {
+            options: &values,
+            filter: options.filter(),
+            is_filtering,
+            offset: options.page_offset(),
+            match_count: options.matching_count(),
+            generation,
+            actions: &actions,
+            directory: None,
+        },
+        layer_stack: context.layers,
+        actions: &parameter_actions,
+    };
+    ctx.view.render(ViewModel::ParameterInput(view_model));
+}
+
+/// An entry of the directory being browsed, labeled with its name. Directories are labeled with a
+/// trailing slash.
+struct PathOption {
+    label: String,
+    entry: DirectoryEntry,
+}
+
+impl AsRef<str> for PathOption {
+    fn as_ref(&self) -> &str {
+        &self.label
+    }
+}
+
+/// State of browsing the filesystem for a path parameter.
+struct PathBrowser {
+    /// Absolute path of the directory the browsing started from.
+    root: String,
+    /// Absolute path of the directory being browsed.
+    directory: String,
+    entries: OptionList<PathOption>,
+    is_filtering: bool,
+    /// Why the directory could not be listed.
+    error: Option<String>,
+}
+
+impl PathBrowser {
+    /// Lists the entries of the directory, replacing the earlier entries and the filter.
+    fn enter(&mut self, ctx: &Context, parameter: &PathParameter, directory: String) {
+        self.entries.clear_filter();
+        self.is_filtering = false;
+        self.directory = directory;
+
+        match ctx
+            .filesystem
+            .list_directory(&self.directory, parameter.glob.as_deref())
+        {
+            Ok(entries) => {
+                self.entries
+                    .set_options(path_entry_options(parameter, entries));
+                self.error = None;
+            }
+            Err(error) => {
+                self.entries.set_options(Vec::new());
+                self.error = Some(filesystem_error_details(&error));
+            }
+        }
+    }
+}
+
+/// Entries which the parameter lists, labeled with their names. Files are left out if the
+/// parameter accepts directories only.
+fn path_entry_options(parameter: &PathParameter, entries: Vec<DirectoryEntry>) -> Vec<PathOption> {
+    entries
+        .into_iter()
+        .filter(|entry| entry.is_directory || parameter.kind.accepts_files())
+        .filter_map(|entry| {
+            let name = Path::new(&entry.path)
+                .file_name()?
+                .to_string_lossy()
+                .into_owned();
+            if name.starts_with('.') && !parameter.hidden {
+                return None;
+            }
+
+            let label = if entry.is_directory {
+                format!("{name}/")
+            } else {
+                name
+            };
+            Some(PathOption { label, entry })
+        })
+        .collect()
+}
+
+fn read_path_parameter(
+    ctx: &Context,
+    context: &ParameterInputContext,
+    parameter_name: &str,
+    parameter: &PathParameter,
+) -> ParameterInputResult {
+    // Without an absolute root, the error surfaces once the root is listed.
+    let root = ctx
+        .filesystem
+        .absolute_path(&parameter.root)
+        .unwrap_or_else(|_| parameter.root.clone());
+
+    let mut browser = PathBrowser {
+        root: root.clone(),
+        directory: String::new(),
+        entries: OptionList::new(Vec::new(), ctx.options_per_page),
+        is_filtering: false,
+        error: None,
+    };
+    browser.enter(ctx, parameter, root);
+    render_path_parameter(ctx, context, parameter_name, parameter, &browser);
+
+    let presses = match ctx.input.capture_any_iter() {
+        Ok(presses) => presses,
+        Err(error) => {
+            run_input_error_mode(ctx, &error);
+            return ParameterInputResult::Exit;
+        }
+    };
+    for press in presses {
         if ctx.keys_deactivate.contains(&press) {
             return ParameterInputResult::Exit;
         }
 
-        if ctx.keys_back.contains(&press) {
-            return ParameterInputResult::Cancel;
+        if let Some(result) = try_skip(ctx, context, &press) {
+            return result;
         }
 
-        let Some(option) = shortcuts.match_fuzzy(&press) else {
-            continue;
-        };
-
-        if let Ok(value) = parameter.try_assign_value((*option).clone()) {
-            return ParameterInputResult::Ok(ParameterValueVariant::Choose(value));
+        if let Some(result) = browse_path(ctx, parameter, &mut browser, &press) {
+            return result;
         }
-        // Invalid value silently ignored; stop regardless.
-        return ParameterInputResult::Exit;
+
+        // Re-render after each keystroke.
+        render_path_parameter(ctx, context, parameter_name, parameter, &browser);
     }
 
     // This is never reached unless the iterator unexpectedly ends.
     ParameterInputResult::Exit
 }
 
-fn read_text_parameter(
+/// Applies a key press to the browser. Returns the result once a path is chosen or the input is
+/// cancelled.
+fn browse_path(
+    ctx: &Context,
+    parameter: &PathParameter,
+    browser: &mut PathBrowser,
+    press: &Key,
+) -> Option<ParameterInputResult> {
+    if browser.is_filtering {
+        // While filtering, the input is directed to the filter instead of the shortcuts.
+        match press.symbol {
+            Symbol::Character(c) => browser.entries.push_filter(c),
+            // Erasing past the beginning of the filter stops filtering.
+            Symbol::BackSpace => browser.is_filtering = browser.entries.pop_filter().is_some(),
+            Symbol::Return | Symbol::Tab => {
+                browser.is_filtering = false;
+
+                // A filter which leaves only one entry is as good as choosing it.
+                if let [option] = browser.entries.current_page().as_slice() {
+                    let entry = &option.entry;
+                    return choose_path_entry(
+                        ctx,
+                        parameter,
+                        browser,
+                        entry.path.clone(),
+                        entry.is_directory,
+                    );
+                }
+            }
+            _ => { /* Irrelevant input. */ }
+        }
+    } else if ctx.keys_back.contains(press) {
+        // Backing out clears an applied filter, and then ascends up to the root before cancelling
+        // the input altogether.
+        if !browser.entries.filter().is_empty() {
+            browser.entries.clear_filter();
+        } else if let Some(parent) = Path::new(&browser.directory).parent()
+            && Path::new(&browser.directory) != Path::new(&browser.root)
+        {
+            let parent = parent.to_string_lossy().into_owned();
+            browser.enter(ctx, parameter, parent);
+        } else {
+            return Some(ParameterInputResult::Cancel);
+        }
+    } else if press.symbol == Symbol::Return && parameter.kind.accepts_directories() {
+        return Some(choose_path(parameter, browser.directory.clone()));
+    } else if press.symbol == Symbol::Tab {
+        browser.is_filtering = true;
+    } else if press.symbol == Symbol::PageDown {
+        browser.entries.next_page();
+    } else if press.symbol == Symbol::PageUp {
+        browser.entries.previous_page();
+    } else {
+        let page = browser.entries.current_page();
+        let shortcuts = assign_option_mnemonics(&page, &BTreeMap::new());
+        if let Some(option) = shortcuts.match_fuzzy(press) {
+            let entry = &option.entry;
+            return choose_path_entry(
+                ctx,
+                parameter,
+                browser,
+                entry.path.clone(),
+                entry.is_directory,
+            );
+        }
+    }
+    None
+}
+
+/// Descends into a directory, or chooses a file.
+fn choose_path_entry(
+    ctx: &Context,
+    parameter: &PathParameter,
+    browser: &mut PathBrowser,
+    path: String,
+    is_directory: bool,
+) -> Option<ParameterInputResult> {
+    if is_directory {
+        browser.enter(ctx, parameter, path);
+        return None;
+    }
+    Some(choose_path(parameter, path))
+}
+
+fn choose_path(parameter: &PathParameter, path: String) -> ParameterInputResult {
+    if let Ok(value) = parameter.try_assign_value(path) {
+        return ParameterInputResult::Ok(ParameterValueVariant::Path(value));
+    }
+    // Invalid value silently ignored; stop regardless.
+    ParameterInputResult::Exit
+}
+
+fn render_path_parameter(
     ctx: &Context,
     context: &ParameterInputContext,
     parameter_name: &str,
-    parameter: &TextParameter,
-) -> ParameterInputResult {
-    let mut input = String::new();
+    parameter: &PathParameter,
+    browser: &PathBrowser,
+) {
+    let page = browser.entries.current_page();
+    let shortcuts = assign_option_mnemonics(&page, &BTreeMap::new());
+    let values: Vec<(&Key, &str)> = shortcuts
+        .iter()
+        .map(|(key, option)| (key, option.label.as_str()))
+        .collect();
 
-    // Render initial view before grabbing the keyboard.
+    let choose_key = Key::new(Symbol::Return);
+    let mut actions: Vec<(&Key, ViewAction)> = vec![];
+    if parameter.kind.accepts_directories() {
+        actions.push((&choose_key, ViewAction::ChooseDirectory));
+    }
+
+    let generation = match &browser.error {
+        None => OptionGenerationStatus::Complete,
+        Some(error_details) => OptionGenerationStatus::Failed {
+            error_details,
+            actions: &[],
+        },
+    };
+
+    let parameter_actions = skip_actions(ctx, context);
     let view_model = ParameterInputViewModel {
         command: context.command,
         parameter_name,
-        parameter: view::ParameterVariant::StringInput {
-            input_value: &input,
+        parameter: view::ParameterVariant::OptionInput {
+            options: &values,
+            filter: browser.entries.filter(),
+            is_filtering: browser.is_filtering,
+            offset: browser.entries.page_offset(),
+            match_count: browser.entries.matching_count(),
+            generation,
+            actions: &actions,
+            directory: Some(&browser.directory),
         },
         layer_stack: context.layers,
+        actions: &parameter_actions,
     };
     ctx.view.render(ViewModel::ParameterInput(view_model));
+}
+
+/// State of a text input, besides the parameter it is read for.
+struct TextInput {
+    buffer: TextBuffer,
+    history: InputHistory,
+    completion: Completion,
+    /// Completion command running in the background.
+    pending_completion: Option<Receiver<Result<String, ExecutorError>>>,
+    /// Why the entered text was not accepted.
+    error: Option<String>,
+}
+
+fn read_text_parameter(
+    ctx: &Context,
+    context: &ParameterInputContext,
+    parameter_name: &str,
+    parameter: &TextParameter,
+) -> ParameterInputResult {
+    let buffer = parameter
+        .default_from
+        .and_then(|selection| ctx.clipboard.read(selection))
+        .map_or_else(TextBuffer::new, |text| {
+            TextBuffer::with_text(single_line(&text))
+        });
+
+    // Inputs opted out of the history neither remember nor offer earlier values.
+    let scope = parameter_scope(context, parameter_name);
+    let history = if parameter.history {
+        let entries = ctx.storage.load_history(&scope).unwrap_or_else(|error| {
+            log_storage_error("load the input history", &error);
+            vec![]
+        });
+        InputHistory::new(entries)
+    } else {
+        InputHistory::default()
+    };
+
+    let mut input = TextInput {
+        buffer,
+        history,
+        completion: Completion::new(),
+        pending_completion: parameter
+            .complete
+            .as_ref()
+            .and_then(|command| start_completion(ctx, context, command)),
+        error: None,
+    };
+
+    // Render initial view before grabbing the keyboard.
+    render_text_input(ctx, context, parameter_name, &input);
+
+    let events = match ctx.input.capture_text_timed_iter(GENERATION_POLL_INTERVAL) {
+        Ok(events) => events,
+        Err(error) => {
+            run_input_error_mode(ctx, &error);
+            return ParameterInputResult::Exit;
+        }
+    };
+    for event in events {
+        let press = match event {
+            Some(TextEvent::Key(press)) => press,
+            Some(TextEvent::Text(text)) => {
+                input.error = None;
+                input.completion.reset();
+                input.buffer.insert_str(&single_line(&text));
+                render_text_input(ctx, context, parameter_name, &input);
+                continue;
+            }
+            None => {
+                if poll_completion(&mut input) {
+                    render_text_input(ctx, context, parameter_name, &input);
+                }
+                continue;
+            }
+        };
 
-    for press in ctx.input.capture_any_iter() {
         if ctx.keys_deactivate.contains(&press) {
             return ParameterInputResult::Exit;
         }
 
-        if ctx.keys_back.contains(&press) && input.is_empty() {
+        if let Some(result) = try_skip(ctx, context, &press) {
+            return result;
+        }
+
+        if ctx.keys_back.contains(&press) && input.buffer.is_empty() {
             return ParameterInputResult::Cancel;
         }
 
-        match press.symbol {
-            Symbol::Character(c) => {
-                input.push(c);
-            }
-            Symbol::Return => {
-                if let Ok(value) = parameter.try_assign_value(input) {
+        // The validation error is shown until the next keystroke.
+        input.error = None;
+
+        if press.symbol == Symbol::Return {
+            match parameter.try_assign_value(input.buffer.text().to_string()) {
+                Ok(value) => {
+                    if parameter.history && !input.buffer.is_empty() {
+                        input.history.record(input.buffer.text());
+                        if let Err(error) =
+                            ctx.storage.store_history(&scope, input.history.entries())
+                        {
+                            log_storage_error("store the input history", &error);
+                        }
+                    }
                     return ParameterInputResult::Ok(ParameterValueVariant::Text(value));
                 }
-                // Invalid value. Silently ignored for now.
-                input = String::new();
+                Err(details) => input.error = Some(details),
             }
-            Symbol::BackSpace => {
-                input.pop();
+        } else if press.symbol == Symbol::Tab {
+            // Tab accepts the next completion, while keeping the earlier matches available.
+            if let Some(candidate) = input.completion.next(input.buffer.text()) {
+                input.buffer = TextBuffer::with_text(candidate.to_string());
             }
-            _ => { /* Irrelevant input. */ }
+        } else {
+            input.completion.reset();
+            edit_text_input(ctx, &mut input, &press);
         }
 
         // Re-render after each keystroke.
-        let view_model = ParameterInputViewModel {
-            command: context.command,
-            parameter_name,
-            parameter: view::ParameterVariant::StringInput {
-                input_value: &input,
-            },
-            layer_stack: context.layers,
+        render_text_input(ctx, context, parameter_name, &input);
+    }
+
+    // This is never reached unless the iterator unexpectedly ends.
+    ParameterInputResult::Exit
+}
+
+/// Applies the history, paste and line editing keys to the text input.
+fn edit_text_input(ctx: &Context, input: &mut TextInput, press: &Key) {
+    if press.symbol == Symbol::Up {
+        if let Some(entry) = input.history.older(input.buffer.text()) {
+            input.buffer = TextBuffer::with_text(entry.to_string());
+        }
+    } else if press.symbol == Symbol::Down {
+        if let Some(entry) = input.history.newer() {
+            input.buffer = TextBuffer::with_text(entry.to_string());
+        }
+    } else if let Some(selection) = paste_selection(press) {
+        if let Some(text) = ctx.clipboard.read(selection) {
+            input.buffer.insert_str(&single_line(&text));
+        }
+    } else {
+        edit_text(&mut input.buffer, press);
+    }
+}
+
+/// Starts the completion command in the background. Returns nothing if the command refers to an
+/// unknown parameter.
+fn start_completion(
+    ctx: &Context,
+    context: &ParameterInputContext,
+    command: &OptionsCommandTemplate,
+) -> Option<Receiver<Result<String, ExecutorError>>> {
+    // The command may refer to the values of the earlier parameters.
+    let values: Vec<String> = context
+        .previous_values
+        .iter()
+        .map(ParameterValueVariant::to_substitution_string)
+        .collect();
+
+    let command = command.render(&values).ok()?;
+    Some(ctx.executor.run_to_completion_async(&command))
+}
+
+/// Takes the candidates into use once the completion command finishes. Returns whether the input
+/// changed.
+fn poll_completion(input: &mut TextInput) -> bool {
+    let Some(receiver) = &input.pending_completion else {
+        return false;
+    };
+
+    let result = match receiver.try_recv() {
+        Ok(result) => result,
+        Err(TryRecvError::Empty) => return false,
+        Err(TryRecvError::Disconnected) => Err(ExecutorError::UnknownError),
+    };
+    input.pending_completion = None;
+
+    match result {
+        Ok(output) => {
+            let candidates = output
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .unique()
+                .map(str::to_string)
+                .collect();
+            input.completion.set_candidates(candidates);
+        }
+        Err(error) => {
+            input.error = Some(format!("Completion failed: {}", error_details(&error)));
+        }
+    }
+    true
+}
+
+fn read_secret_parameter(
+    ctx: &Context,
+    context: &ParameterInputContext,
+    parameter_name: &str,
+    parameter: &SecretParameter,
+) -> ParameterInputResult {
+    // The value is produced by the command in the background if possible. The user may type the
+    // value meanwhile, and is asked to type it if the command fails.
+    let mut input = SecretInput {
+        buffer: TextBuffer::new(),
+        pending_command: None,
+        error: None,
+    };
+    if let Some(command) = &parameter.command {
+        match start_secret_command(ctx, context, command) {
+            Ok(receiver) => input.pending_command = Some(receiver),
+            Err(details) => input.error = Some(details),
+        }
+    }
+
+    // Render initial view before grabbing the keyboard.
+    render_secret_input(ctx, context, parameter_name, &input);
+
+    // Typing a secret is text entry, where holding a key such as Backspace repeats it.
+    let events = match ctx.input.capture_text_timed_iter(GENERATION_POLL_INTERVAL) {
+        Ok(events) => events,
+        Err(error) => {
+            run_input_error_mode(ctx, &error);
+            return ParameterInputResult::Exit;
+        }
+    };
+    for event in events {
+        if let Some(result) = poll_secret_command(&mut input) {
+            match result {
+                // The value which the user has begun to type takes precedence.
+                Ok(secret) if input.buffer.is_empty() => {
+                    if let Ok(value) = parameter.try_assign_value(secret) {
+                        return ParameterInputResult::Ok(ParameterValueVariant::Secret(value));
+                    }
+                }
+                Ok(_) => {}
+                Err(details) => input.error = Some(details),
+            }
+            render_secret_input(ctx, context, parameter_name, &input);
+        }
+        let press = match event {
+            Some(TextEvent::Key(press)) => press,
+            Some(TextEvent::Text(text)) => {
+                input.error = None;
+                input.buffer.insert_str(&single_line(&text));
+                render_secret_input(ctx, context, parameter_name, &input);
+                continue;
+            }
+            None => continue,
         };
-        ctx.view.render(ViewModel::ParameterInput(view_model));
+
+        if ctx.keys_deactivate.contains(&press) {
+            return ParameterInputResult::Exit;
+        }
+
+        if let Some(result) = try_skip(ctx, context, &press) {
+            return result;
+        }
+
+        if ctx.keys_back.contains(&press) && input.buffer.is_empty() {
+            return ParameterInputResult::Cancel;
+        }
+
+        // The error is shown until the next keystroke.
+        input.error = None;
+
+        if press.symbol == Symbol::Return {
+            if let Ok(value) = parameter.try_assign_value(input.buffer.text().to_string()) {
+                return ParameterInputResult::Ok(ParameterValueVariant::Secret(value));
+            }
+        } else if let Some(selection) = paste_selection(&press) {
+            if let Some(text) = ctx.clipboard.read(selection) {
+                input.buffer.insert_str(&single_line(&text));
+            }
+        } else {
+            edit_text(&mut input.buffer, &press);
+        }
+
+        render_secret_input(ctx, context, parameter_name, &input);
     }
 
     // This is never reached unless the iterator unexpectedly ends.
     ParameterInputResult::Exit
 }
+
+/// State of typing a secret, while the command producing it may still be running.
+struct SecretInput {
+    buffer: TextBuffer,
+    /// The command producing the secret, while it runs.
+    pending_command: Option<Receiver<Result<String, ExecutorError>>>,
+    /// Why the command could not produce the secret.
+    error: Option<String>,
+}
+
+/// Starts the command producing the secret in the background.
+fn start_secret_command(
+    ctx: &Context,
+    context: &ParameterInputContext,
+    command: &OptionsCommandTemplate,
+) -> Result<Receiver<Result<String, ExecutorError>>, String> {
+    // The command may refer to the values of the earlier parameters.
+    let values: Vec<String> = context
+        .previous_values
+        .iter()
+        .map(ParameterValueVariant::to_substitution_string)
+        .collect();
+    let command = command
+        .render(&values)
+        .map_err(|_| "Secret command refers to an unknown parameter".to_string())?;
+
+    Ok(ctx.executor.run_to_completion_async(&command))
+}
+
+/// Returns the first line of the output once the secret command finishes. A command which prints
+/// nothing has failed to produce the secret.
+fn poll_secret_command(input: &mut SecretInput) -> Option<Result<String, String>> {
+    let receiver = input.pending_command.as_ref()?;
+    let result = match receiver.try_recv() {
+        Ok(result) => result,
+        Err(TryRecvError::Empty) => return None,
+        Err(TryRecvError::Disconnected) => Err(ExecutorError::UnknownError),
+    };
+    input.pending_command = None;
+
+    Some(match result {
+        Ok(output) => match output.lines().next() {
+            Some(line) if !line.is_empty() => Ok(line.to_string()),
+            _ => Err("Secret command printed nothing".to_string()),
+        },
+        Err(error) => Err(format!("Secret command failed: {}", error_details(&error))),
+    })
+}
+
+fn render_secret_input(
+    ctx: &Context,
+    context: &ParameterInputContext,
+    parameter_name: &str,
+    input: &SecretInput,
+) {
+    let parameter_actions = skip_actions(ctx, context);
+    let view_model = ParameterInputViewModel {
+        command: context.command,
+        parameter_name,
+        parameter: view::ParameterVariant::SecretInput {
+            length: input.buffer.text().chars().count(),
+            cursor: input.buffer.cursor(),
+            is_loading: input.pending_command.is_some(),
+            error: input.error.as_deref(),
+        },
+        layer_stack: context.layers,
+        actions: &parameter_actions,
+    };
+    ctx.view.render(ViewModel::ParameterInput(view_model));
+}
+
+/// Applies the line editing key to the text. Keys without an editing action are ignored.
+fn edit_text(input: &mut TextBuffer, press: &Key) {
+    let control = press.modifiers.contains(&Modifier::Control);
+
+    match press.symbol {
+        Symbol::Character('w') if control => input.delete_word_backward(),
+        Symbol::Character('u') if control => input.delete_to_start(),
+        Symbol::Character(c) if !control => input.insert(c),
+        Symbol::BackSpace => {
+            input.delete_backward();
+        }
+        Symbol::Delete => {
+            input.delete_forward();
+        }
+        Symbol::Left => input.move_left(),
+        Symbol::Right => input.move_right(),
+        Symbol::Home => input.move_home(),
+        Symbol::End => input.move_end(),
+        _ => { /* Irrelevant input. */ }
+    }
+}
+
+/// Selection pasted with the key: Ctrl+V pastes the clipboard, and Shift+Insert the primary
+/// selection.
+fn paste_selection(press: &Key) -> Option<Selection> {
+    match press.symbol {
+        Symbol::Character('v') if press.modifiers.contains(&Modifier::Control) => {
+            Some(Selection::Clipboard)
+        }
+        Symbol::Insert if press.modifiers.contains(&Modifier::Shift) => Some(Selection::Primary),
+        _ => None,
+    }
+}
+
+/// Joins the lines of the pasted text, as the text input holds a single line.
+fn single_line(text: &str) -> String {
+    text.lines().map(str::trim_end).join(" ")
+}
+
+fn render_text_input(
+    ctx: &Context,
+    context: &ParameterInputContext,
+    parameter_name: &str,
+    input: &TextInput,
+) {
+    // The completions are shown a page at a time, the page following the accepted completion.
+    let matches = input.completion.matches(input.buffer.text());
+    let selected = input.completion.selected();
+    let page_start = selected.map_or(0, |index| index - index % ctx.options_per_page);
+    let completions: Vec<&str> = matches
+        .into_iter()
+        .skip(page_start)
+        .take(ctx.options_per_page)
+        .collect();
+
+    let parameter_actions = skip_actions(ctx, context);
+    let view_model = ParameterInputViewModel {
+        command: context.command,
+        parameter_name,
+        parameter: view::ParameterVariant::StringInput {
+            input_value: input.buffer.text(),
+            cursor: input.buffer.cursor(),
+            placeholder: input.history.most_recent(),
+            error: input.error.as_deref(),
+            completions: &completions,
+            selected_completion: selected.map(|index| index - page_start),
+        },
+        layer_stack: context.layers,
+        actions: &parameter_actions,
+    };
+    ctx.view.render(ViewModel::ParameterInput(view_model));
+}
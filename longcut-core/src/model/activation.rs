@@ -0,0 +1,164 @@
+use crate::model::key::Key;
+use std::time::Duration;
+
+/// How long a tap of an activation key may last unless configured otherwise.
+pub const DEFAULT_TAP_DURATION: Duration = Duration::from_millis(250);
+
+/// How long may pass between the taps of a double tap unless configured otherwise.
+pub const DEFAULT_DOUBLE_TAP_INTERVAL: Duration = Duration::from_millis(400);
+
+/// How pressing an activation key activates the program.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Activation {
+    /// Pressing the key activates right away. The key cannot be used for anything else.
+    #[default]
+    Press,
+    /// Pressing and releasing the key on its own within the duration activates. Other presses,
+    /// including combinations with the key, reach the focused window as usual.
+    Tap { max_duration: Duration },
+    /// Tapping the same key twice activates, if the second tap begins within the interval after
+    /// the first one. Single taps reach the focused window as usual.
+    DoubleTap {
+        max_duration: Duration,
+        max_interval: Duration,
+    },
+}
+
+/// A key activating the program, along with how pressing it activates.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ActivationKey {
+    pub key: Key,
+    pub activation: Activation,
+}
+
+/// Tells the activations apart among the captured presses and taps of the activation keys. A
+/// double tap key activates once it is tapped again within its interval, while the other keys
+/// activate right away.
+#[derive(Debug)]
+pub struct ActivationTracker<'a> {
+    keys: &'a [ActivationKey],
+    /// The key tapped once, along with the interval in which its second tap must begin.
+    first_tap: Option<(Key, Duration)>,
+}
+
+impl<'a> ActivationTracker<'a> {
+    #[must_use]
+    pub fn new(keys: &'a [ActivationKey]) -> Self {
+        Self {
+            keys,
+            first_tap: None,
+        }
+    }
+
+    /// How long to wait for the next capture, or `None` to wait for as long as it takes.
+    #[must_use]
+    pub fn timeout(&self) -> Option<Duration> {
+        self.first_tap.as_ref().map(|(_, interval)| *interval)
+    }
+
+    /// Takes in the captured key, or `None` if the timeout passed first. Returns the key if it
+    /// activates the program.
+    pub fn capture(&mut self, captured: Option<Key>) -> Option<Key> {
+        let first_tap = self.first_tap.take();
+        let key = captured?;
+        let activation = self
+            .keys
+            .iter()
+            .find(|activation_key| activation_key.key == key)?;
+        let Activation::DoubleTap { max_interval, .. } = activation.activation else {
+            return Some(key);
+        };
+
+        if first_tap.is_some_and(|(first, _)| first == key) {
+            return Some(key);
+        }
+
+        // A tap of another key may still be the first tap of its double tap.
+        self.first_tap = Some((key, max_interval));
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::key::Symbol;
+
+    fn activation_keys() -> Vec<ActivationKey> {
+        let double_tap = Activation::DoubleTap {
+            max_duration: DEFAULT_TAP_DURATION,
+            max_interval: DEFAULT_DOUBLE_TAP_INTERVAL,
+        };
+        vec![
+            ActivationKey {
+                key: Key::new(Symbol::AltL),
+                activation: double_tap,
+            },
+            ActivationKey {
+                key: Key::new(Symbol::AltR),
+                activation: double_tap,
+            },
+            ActivationKey {
+                key: Key::new(Symbol::SuperL),
+                activation: Activation::Press,
+            },
+        ]
+    }
+
+    #[test]
+    fn second_tap_within_the_interval_activates() {
+        let keys = activation_keys();
+        let mut tracker = ActivationTracker::new(&keys);
+        assert_eq!(tracker.timeout(), None);
+
+        assert_eq!(tracker.capture(Some(Key::new(Symbol::AltL))), None);
+        assert_eq!(tracker.timeout(), Some(DEFAULT_DOUBLE_TAP_INTERVAL));
+        assert_eq!(
+            tracker.capture(Some(Key::new(Symbol::AltL))),
+            Some(Key::new(Symbol::AltL))
+        );
+    }
+
+    #[test]
+    fn second_tap_after_the_interval_counts_as_a_first_tap() {
+        let keys = activation_keys();
+        let mut tracker = ActivationTracker::new(&keys);
+
+        assert_eq!(tracker.capture(Some(Key::new(Symbol::AltL))), None);
+        assert_eq!(tracker.capture(None), None);
+        assert_eq!(tracker.timeout(), None);
+
+        assert_eq!(tracker.capture(Some(Key::new(Symbol::AltL))), None);
+        assert_eq!(
+            tracker.capture(Some(Key::new(Symbol::AltL))),
+            Some(Key::new(Symbol::AltL))
+        );
+    }
+
+    #[test]
+    fn tap_of_another_key_between_the_taps_starts_over() {
+        let keys = activation_keys();
+        let mut tracker = ActivationTracker::new(&keys);
+
+        assert_eq!(tracker.capture(Some(Key::new(Symbol::AltL))), None);
+        assert_eq!(tracker.capture(Some(Key::new(Symbol::AltR))), None);
+        assert_eq!(tracker.capture(Some(Key::new(Symbol::AltL))), None);
+        assert_eq!(
+            tracker.capture(Some(Key::new(Symbol::AltL))),
+            Some(Key::new(Symbol::AltL))
+        );
+    }
+
+    #[test]
+    fn press_activates_right_away() {
+        let keys = activation_keys();
+        let mut tracker = ActivationTracker::new(&keys);
+
+        assert_eq!(tracker.capture(Some(Key::new(Symbol::AltL))), None);
+        assert_eq!(
+            tracker.capture(Some(Key::new(Symbol::SuperL))),
+            Some(Key::new(Symbol::SuperL))
+        );
+        assert_eq!(tracker.timeout(), None);
+    }
+}
@@ -1,18 +1,56 @@
 use crate::model::command::EffectRenderError::ParameterMissing;
 use crate::model::effect::{Effect, EffectTemplate};
-use crate::model::parameter::{Parameter, ParameterDefinitionVariant, ParameterValueVariant};
+use crate::model::option_source::OptionSource;
+use crate::model::parameter::{
+    ChooseParameter, Parameter, ParameterDefinitionVariant, ParameterValueVariant, SecretParameter,
+    TextParameter,
+};
 use itertools::{EitherOrBoth, Itertools};
+use std::time::Duration;
+
+/// How long a key must be held to run the hold action of its command unless configured otherwise.
+pub const DEFAULT_HOLD_DURATION: Duration = Duration::from_millis(500);
 
 #[derive(Debug)]
 pub struct CommandParameter {
     pub name: String,
     pub parameter: ParameterDefinitionVariant,
+    /// Whether the input of the parameter can be skipped, leaving the parameter to its default.
+    pub optional: bool,
+    /// Value of the skipped parameter. Without a default, only a text parameter which is not
+    /// required can be skipped, leaving it empty.
+    pub default: Option<String>,
 }
 
 impl CommandParameter {
     #[must_use]
     pub fn new(name: String, parameter: ParameterDefinitionVariant) -> Self {
-        Self { name, parameter }
+        Self {
+            name,
+            parameter,
+            optional: false,
+            default: None,
+        }
+    }
+
+    /// Value of the parameter when its input is skipped, or nothing if the parameter cannot be
+    /// skipped.
+    #[must_use]
+    pub fn skip_value(&self) -> Option<&str> {
+        if !self.optional {
+            return None;
+        }
+
+        match (&self.default, &self.parameter) {
+            (Some(default), _) => Some(default),
+            (
+                None,
+                ParameterDefinitionVariant::Text(TextParameter {
+                    required: false, ..
+                }),
+            ) => Some(""),
+            _ => None,
+        }
     }
 }
 
@@ -22,6 +60,8 @@ pub struct Command {
     steps: Vec<EffectTemplate>,
     parameters: Vec<CommandParameter>,
     pub is_final: bool,
+    /// Command run instead when the key of the command is held down.
+    hold: Option<Box<Command>>,
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -29,6 +69,18 @@ pub enum CommandError {
     NoStepsProvided,
     MissingParameter(usize),
     UnusedParameter(usize),
+    /// Options or the value of a parameter are produced by a command using the value of a
+    /// parameter which is not read before it.
+    LaterParameterReferenced {
+        parameter: usize,
+        referenced: usize,
+    },
+    /// Options or the value of a parameter are produced by a command naming a parameter which
+    /// does not exist.
+    UnknownParameterReferenced {
+        parameter: usize,
+        name: String,
+    },
 }
 
 #[derive(Debug)]
@@ -45,7 +97,7 @@ impl Command {
     pub fn new(
         name: String,
         steps: Vec<EffectTemplate>,
-        parameters: Vec<CommandParameter>,
+        mut parameters: Vec<CommandParameter>,
     ) -> Result<Self, CommandError> {
         // Command without any steps makes no sense.
         if steps.is_empty() {
@@ -61,6 +113,46 @@ impl Command {
             required_parameters.insert(parameter);
         }
 
+        // Option generators, secret commands and completion commands may only refer to the
+        // parameters which are read before them. The parameters they refer to count as required
+        // as well.
+        let parameter_names: Vec<String> = parameters.iter().map(|p| p.name.clone()).collect();
+        for (idx, parameter) in parameters.iter_mut().enumerate() {
+            let (ParameterDefinitionVariant::Choose(ChooseParameter {
+                gen_options_source:
+                    Some(OptionSource::Command {
+                        command: template, ..
+                    }),
+                ..
+            })
+            | ParameterDefinitionVariant::Secret(SecretParameter {
+                command: Some(template),
+            })
+            | ParameterDefinitionVariant::Text(TextParameter {
+                complete: Some(template),
+                ..
+            })) = &mut parameter.parameter
+            else {
+                continue;
+            };
+
+            template.resolve_names(&parameter_names).map_err(|name| {
+                CommandError::UnknownParameterReferenced {
+                    parameter: idx,
+                    name,
+                }
+            })?;
+            for referenced in template.get_required_parameters() {
+                if referenced >= idx {
+                    return Err(CommandError::LaterParameterReferenced {
+                        parameter: idx,
+                        referenced,
+                    });
+                }
+                required_parameters.insert(referenced);
+            }
+        }
+
         // Every required parameter must be declared.
         for idx in &required_parameters {
             if parameters.get(*idx).is_none() {
@@ -80,6 +172,7 @@ impl Command {
             steps,
             parameters,
             is_final: false,
+            hold: None,
         })
     }
 
@@ -93,6 +186,16 @@ impl Command {
         self
     }
 
+    #[must_use]
+    pub fn get_hold(&self) -> Option<&Command> {
+        self.hold.as_deref()
+    }
+
+    pub fn set_hold(&mut self, command: Command) -> &mut Self {
+        self.hold = Some(Box::new(command));
+        self
+    }
+
     /// Renders out the command into an [Effect] sequence.
     ///
     /// The provided parameter values must equal in order, in type, and in value compatibility the
@@ -121,7 +224,7 @@ impl Command {
                     return Err(ParameterMissing);
                 };
 
-                let substitution = format_substitution_string(&definition.parameter, value)?;
+                let substitution = format_substitution_string(definition, value)?;
                 substitutions.push(substitution);
             }
 
@@ -146,17 +249,25 @@ impl Command {
 
         /// Returns the substitution string for a single parameter definition-value -pair if possible.
         fn format_substitution_string(
-            parameter_definition: &ParameterDefinitionVariant,
+            parameter: &CommandParameter,
             parameter_value: ParameterValueVariant,
         ) -> Result<String, EffectRenderError> {
             use ParameterDefinitionVariant as Def;
             use ParameterValueVariant as Val;
 
+            // A skipped parameter takes its default value.
+            if let Val::Default(value) = parameter_value {
+                return match parameter.skip_value() {
+                    Some(default) if default == value => Ok(value),
+                    _ => Err(EffectRenderError::ParameterDefinitionAndValueMismatch),
+                };
+            }
+
             // Provided values must match the definitions. Although the parameter values are
             // already checked for validity during assignment operation, that type guarantee does
             // not carry through to here. We therefore perform another assignment to make sure that
             // every value matches the definition.
-            match (&parameter_definition, parameter_value) {
+            match (&parameter.parameter, parameter_value) {
                 // Character parameter
                 (Def::Character(definition), Val::Character(value)) => {
                     let Ok(verified) = definition.try_assign_value(value.take()) else {
@@ -175,6 +286,24 @@ impl Command {
                     Ok(verified.take().clone())
                 }
 
+                // Path parameter
+                (Def::Path(definition), Val::Path(value)) => {
+                    let Ok(verified) = definition.try_assign_value(value.take()) else {
+                        return Err(EffectRenderError::ParameterDefinitionAndValueMismatch);
+                    };
+
+                    Ok(verified.take().clone())
+                }
+
+                // Secret parameter
+                (Def::Secret(definition), Val::Secret(value)) => {
+                    let Ok(verified) = definition.try_assign_value(value.take()) else {
+                        return Err(EffectRenderError::ParameterDefinitionAndValueMismatch);
+                    };
+
+                    Ok(verified.take().expose().to_string())
+                }
+
                 // Text parameter
                 (Def::Text(definition), Val::Text(value)) => {
                     let Ok(verified) = definition.try_assign_value(value.take()) else {
@@ -199,7 +328,9 @@ impl Command {
 mod command_tests {
     use super::*;
     use crate::model::effect::ShellCommandTemplate;
-    use crate::model::parameter::TextParameter;
+    use crate::model::option_source::{OutputFormat, Separator};
+    use crate::model::options_command::OptionsCommandTemplate;
+    use crate::model::parameter::{ChooseParameter, TextParameter};
 
     #[test]
     fn can_build_parameterless_command() {
@@ -232,7 +363,7 @@ mod command_tests {
             EffectTemplate::ShellCommand(ShellCommandTemplate::new("echo 'Hi {0}!'").unwrap());
         let param_target = CommandParameter::new(
             "Example".into(),
-            ParameterDefinitionVariant::Text(TextParameter),
+            ParameterDefinitionVariant::Text(TextParameter::default()),
         );
         let result = Command::new("Greet".into(), vec![greet_target], vec![param_target]);
         assert!(result.is_ok());
@@ -253,28 +384,176 @@ mod command_tests {
             EffectTemplate::ShellCommand(ShellCommandTemplate::new("echo 'Hello!'").unwrap());
         let param_target = CommandParameter::new(
             "Example".into(),
-            ParameterDefinitionVariant::Text(TextParameter),
+            ParameterDefinitionVariant::Text(TextParameter::default()),
         );
         let result = Command::new("Greet".into(), vec![greet_target], vec![param_target]);
         assert!(result.is_err());
         assert_eq!(result.unwrap_err(), CommandError::UnusedParameter(0));
     }
 
+    fn choose_parameter(name: &str, gen_command: &str) -> CommandParameter {
+        let source = OptionSource::Command {
+            command: OptionsCommandTemplate::new(gen_command).unwrap(),
+            format: OutputFormat::Split(Separator::Literal("\n".to_string())),
+        };
+        let choose = ChooseParameter::new(None, Some(source), None).unwrap();
+        CommandParameter::new(name.into(), ParameterDefinitionVariant::Choose(choose))
+    }
+
+    #[test]
+    fn generators_may_refer_to_earlier_parameters() {
+        let step =
+            EffectTemplate::ShellCommand(ShellCommandTemplate::new("kubectl use {1}").unwrap());
+        let parameters = vec![
+            choose_parameter("Context", "kubectl config get-contexts -o name"),
+            choose_parameter("Namespace", "kubectl --context {Context} get ns"),
+        ];
+        let result = Command::new("Switch".into(), vec![step], parameters);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn generators_must_not_refer_to_later_parameters() {
+        let step = EffectTemplate::ShellCommand(ShellCommandTemplate::new("echo {0} {1}").unwrap());
+        let parameters = vec![
+            choose_parameter("First", "ls {1}"),
+            choose_parameter("Second", "ls"),
+        ];
+        let result = Command::new("List".into(), vec![step], parameters);
+        assert_eq!(
+            result.unwrap_err(),
+            CommandError::LaterParameterReferenced {
+                parameter: 0,
+                referenced: 1
+            }
+        );
+    }
+
+    #[test]
+    fn generators_must_not_refer_to_unknown_parameters() {
+        let step = EffectTemplate::ShellCommand(ShellCommandTemplate::new("echo {1}").unwrap());
+        let parameters = vec![
+            choose_parameter("Project", "ls ~/projects"),
+            choose_parameter("Branch", "git -C ~/projects/{Projct} branch"),
+        ];
+        let result = Command::new("Checkout".into(), vec![step], parameters);
+        assert_eq!(
+            result.unwrap_err(),
+            CommandError::UnknownParameterReferenced {
+                parameter: 1,
+                name: "Projct".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn secret_commands_count_as_referring_to_parameters() {
+        let step = EffectTemplate::ShellCommand(
+            ShellCommandTemplate::new("psql -U postgres -W {1}").unwrap(),
+        );
+        let secret = SecretParameter {
+            command: Some(OptionsCommandTemplate::new("pass show db/{Host}").unwrap()),
+        };
+        let parameters = vec![
+            CommandParameter::new(
+                "Host".into(),
+                ParameterDefinitionVariant::Text(TextParameter::default()),
+            ),
+            CommandParameter::new(
+                "Password".into(),
+                ParameterDefinitionVariant::Secret(secret),
+            ),
+        ];
+        let result = Command::new("Connect".into(), vec![step], parameters);
+        assert!(result.is_ok());
+    }
+
     #[test]
     fn command_effects_can_be_rendered() {
         let greet_target =
             EffectTemplate::ShellCommand(ShellCommandTemplate::new("echo 'Hello {0}'").unwrap());
         let param_target = CommandParameter::new(
             "Example".into(),
-            ParameterDefinitionVariant::Text(TextParameter),
+            ParameterDefinitionVariant::Text(TextParameter::default()),
         );
         let command = Command::new("Greet".into(), vec![greet_target], vec![param_target]).unwrap();
         let values = vec![ParameterValueVariant::Text(
-            TextParameter.try_assign_value("World").unwrap(),
+            TextParameter::default().try_assign_value("World").unwrap(),
         )];
         let effects = command.render_effects(values).unwrap();
         assert_eq!(effects.len(), 1);
         let Effect::ShellCommand { program, .. } = &effects[0];
         assert_eq!(program, "echo 'Hello World'");
     }
+    fn optional_parameter(parameter: ParameterDefinitionVariant) -> CommandParameter {
+        let mut parameter = CommandParameter::new("Example".into(), parameter);
+        parameter.optional = true;
+        parameter
+    }
+
+    #[test]
+    fn only_parameters_with_a_value_to_leave_can_be_skipped() {
+        let text = CommandParameter::new(
+            "Example".into(),
+            ParameterDefinitionVariant::Text(TextParameter::default()),
+        );
+        assert_eq!(text.skip_value(), None);
+
+        let optional_text =
+            optional_parameter(ParameterDefinitionVariant::Text(TextParameter::default()));
+        assert_eq!(optional_text.skip_value(), Some(""));
+
+        let required_text = optional_parameter(ParameterDefinitionVariant::Text(TextParameter {
+            required: true,
+            ..TextParameter::default()
+        }));
+        assert_eq!(required_text.skip_value(), None);
+
+        let choose = || {
+            ChooseParameter::new(Some(vec!["a".to_string(), "b".to_string()]), None, None).unwrap()
+        };
+        let optional_choose = optional_parameter(ParameterDefinitionVariant::Choose(choose()));
+        assert_eq!(optional_choose.skip_value(), None);
+
+        let mut defaulted_choose = optional_parameter(ParameterDefinitionVariant::Choose(choose()));
+        defaulted_choose.default = Some("b".to_string());
+        assert_eq!(defaulted_choose.skip_value(), Some("b"));
+    }
+
+    #[test]
+    fn skipped_parameter_is_substituted_with_its_default() {
+        let step = EffectTemplate::ShellCommand(ShellCommandTemplate::new("ssh -p {0}").unwrap());
+        let mut port =
+            optional_parameter(ParameterDefinitionVariant::Text(TextParameter::default()));
+        port.default = Some("22".to_string());
+        let command = Command::new("Connect".into(), vec![step], vec![port]).unwrap();
+
+        let effects = command
+            .render_effects(vec![ParameterValueVariant::Default("22".to_string())])
+            .unwrap();
+        let Effect::ShellCommand { program, .. } = &effects[0];
+        assert_eq!(program, "ssh -p 22");
+
+        let result = command.render_effects(vec![ParameterValueVariant::Default("23".to_string())]);
+        assert!(matches!(
+            result,
+            Err(EffectRenderError::ParameterDefinitionAndValueMismatch)
+        ));
+    }
+
+    #[test]
+    fn parameter_without_a_skip_value_cannot_be_defaulted() {
+        let step = EffectTemplate::ShellCommand(ShellCommandTemplate::new("echo {0}").unwrap());
+        let text = CommandParameter::new(
+            "Example".into(),
+            ParameterDefinitionVariant::Text(TextParameter::default()),
+        );
+        let command = Command::new("Echo".into(), vec![step], vec![text]).unwrap();
+
+        let result = command.render_effects(vec![ParameterValueVariant::Default(String::new())]);
+        assert!(matches!(
+            result,
+            Err(EffectRenderError::ParameterDefinitionAndValueMismatch)
+        ));
+    }
 }
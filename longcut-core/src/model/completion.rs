@@ -0,0 +1,113 @@
+/// Candidates completing the text typed so far.
+///
+/// The candidates starting with the typed text are its matches. Cycling through the matches keeps
+/// matching against the text typed before cycling began, so that the accepted matches do not
+/// narrow down the matches any further.
+#[derive(Debug, Default)]
+pub struct Completion {
+    candidates: Vec<String>,
+    /// Text the candidates are matched against while cycling, and the index of the current match.
+    cycle: Option<(String, usize)>,
+}
+
+impl Completion {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replaces the candidates, stopping any cycling.
+    pub fn set_candidates(&mut self, candidates: Vec<String>) {
+        self.candidates = candidates;
+        self.cycle = None;
+    }
+
+    /// Candidates which complete the input, or the text typed before cycling began.
+    #[must_use]
+    pub fn matches(&self, input: &str) -> Vec<&str> {
+        let prefix = self.cycle.as_ref().map_or(input, |(prefix, _)| prefix);
+        self.candidates
+            .iter()
+            .map(String::as_str)
+            .filter(|candidate| candidate.starts_with(prefix))
+            .collect()
+    }
+
+    /// Index of the current match among the matches, if cycling.
+    #[must_use]
+    pub fn selected(&self) -> Option<usize> {
+        self.cycle.as_ref().map(|(_, index)| *index)
+    }
+
+    /// Steps to the next match, wrapping around after the last one. Returns nothing if nothing
+    /// matches the input.
+    pub fn next(&mut self, input: &str) -> Option<&str> {
+        let match_count = self.matches(input).len();
+        if match_count == 0 {
+            return None;
+        }
+
+        self.cycle = Some(match self.cycle.take() {
+            Some((prefix, index)) => (prefix, (index + 1) % match_count),
+            None => (input.to_string(), 0),
+        });
+
+        let index = self.selected()?;
+        self.matches(input).get(index).copied()
+    }
+
+    /// Stops cycling, so that the matches follow the input again.
+    pub fn reset(&mut self) {
+        self.cycle = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn completion(candidates: &[&str]) -> Completion {
+        let mut completion = Completion::new();
+        completion.set_candidates(candidates.iter().map(ToString::to_string).collect());
+        completion
+    }
+
+    #[test]
+    fn candidates_starting_with_input_match() {
+        let completion = completion(&["main", "feature/a", "feature/b"]);
+        assert_eq!(completion.matches("feat"), vec!["feature/a", "feature/b"]);
+        assert_eq!(
+            completion.matches(""),
+            vec!["main", "feature/a", "feature/b"]
+        );
+        assert!(completion.matches("fix").is_empty());
+    }
+
+    #[test]
+    fn matches_are_cycled_from_the_typed_text() {
+        let mut completion = completion(&["main", "feature/a", "feature/b"]);
+        assert_eq!(completion.next("feat"), Some("feature/a"));
+        assert_eq!(completion.selected(), Some(0));
+
+        // The accepted match does not narrow down the matches.
+        assert_eq!(completion.next("feature/a"), Some("feature/b"));
+        assert_eq!(completion.next("feature/b"), Some("feature/a"));
+        assert_eq!(completion.matches("feature/a").len(), 2);
+    }
+
+    #[test]
+    fn reset_makes_matches_follow_input() {
+        let mut completion = completion(&["main", "feature/a", "feature/b"]);
+        completion.next("feat");
+        completion.reset();
+        assert_eq!(completion.selected(), None);
+        assert_eq!(completion.matches("feature/b"), vec!["feature/b"]);
+    }
+
+    #[test]
+    fn nothing_is_cycled_without_matches() {
+        let mut completion = completion(&["main"]);
+        assert_eq!(completion.next("fix"), None);
+        assert_eq!(completion.selected(), None);
+    }
+}
@@ -1,4 +1,4 @@
-use regex::Regex;
+use crate::model::placeholder::{Segment, split_placeholders};
 use std::collections::BTreeSet;
 
 /// A concrete effect to be carried out. This is the rendered (parameter-substituted)
@@ -15,6 +15,11 @@ pub enum Effect {
 enum Token {
     Text(String),
     Parameter(usize),
+    /// Section which is left out when the value of the parameter is empty.
+    Optional {
+        parameter: usize,
+        body: Vec<Token>,
+    },
 }
 
 #[derive(Debug)]
@@ -29,6 +34,10 @@ pub enum ShellCommandRenderError {
 }
 
 impl ShellCommandTemplate {
+    /// Parameters are substituted into the places marked with `{index}`. Sections marked with
+    /// `{?index: text}` are included only if the value of the parameter is not empty, and may
+    /// contain substitutions of their own, such as in `ls{?0: --sort {0}}`.
+    ///
     /// # Errors
     ///
     /// Returns an error if the program string is empty or contains invalid parameter placeholders.
@@ -41,35 +50,30 @@ impl ShellCommandTemplate {
             return Err("program must not be an empty string".into());
         }
 
-        // Program string is tokenized into a list.
-        let pattern = Regex::new(r"\{([^{}]*)}").unwrap();
-
+        // Optional sections are split out first, and the rest is tokenized around them.
         let mut tokens: Vec<Token> = Vec::new();
-        let mut last_match_end: usize = 0;
-        for capture in pattern.captures_iter(program) {
-            let full_match = capture.get(0).unwrap();
-
-            // Capturing the command between each substitution.
-            let slice = &program[last_match_end..full_match.start()];
-            if !slice.is_empty() {
-                tokens.push(Token::Text(slice.to_string()));
-            }
+        let mut rest = program;
+        while let Some(start) = rest.find("{?") {
+            tokens.extend(tokenize_substitutions(&rest[..start])?);
 
-            // Inserting the actual parameter substitution.
-            let idx_str = capture.get(1).unwrap().as_str();
+            let section = &rest[start + 2..];
+            let end = closing_brace(section)
+                .ok_or_else(|| format!("optional section {} is not closed", &rest[start..]))?;
+            let (idx_str, body) = section[..end]
+                .split_once(':')
+                .ok_or("optional section must be of the form {?index: text}")?;
             let idx = idx_str
+                .trim()
                 .parse()
                 .map_err(|_| format!("{idx_str} is not a valid parameter index"))?;
-            tokens.push(Token::Parameter(idx));
-
-            last_match_end = full_match.end();
-        }
+            tokens.push(Token::Optional {
+                parameter: idx,
+                body: tokenize_substitutions(body)?,
+            });
 
-        // The remainder of the program string is added as the final text token.
-        let slice = &program[last_match_end..];
-        if !slice.is_empty() {
-            tokens.push(Token::Text(slice.to_string()));
+            rest = &section[end + 1..];
         }
+        tokens.extend(tokenize_substitutions(rest)?);
 
         Ok(Self {
             tokens,
@@ -92,20 +96,7 @@ impl ShellCommandTemplate {
         parameters: &[impl AsRef<str>],
     ) -> Result<Effect, ShellCommandRenderError> {
         let mut program = String::new();
-        for token in &self.tokens {
-            match token {
-                Token::Text(str) => {
-                    program.push_str(str);
-                }
-                Token::Parameter(idx) => {
-                    let value = parameters
-                        .get(*idx)
-                        .ok_or(ShellCommandRenderError::MissingParameter)?;
-
-                    program.push_str(value.as_ref());
-                }
-            }
-        }
+        render_tokens(&self.tokens, parameters, &mut program)?;
 
         Ok(Effect::ShellCommand {
             program,
@@ -116,12 +107,82 @@ impl ShellCommandTemplate {
     #[must_use]
     pub fn get_required_parameters(&self) -> BTreeSet<usize> {
         let mut indexes = BTreeSet::new();
-        for token in &self.tokens {
-            if let Token::Parameter(idx) = token {
+        collect_parameters(&self.tokens, &mut indexes);
+        indexes
+    }
+}
+
+/// Tokenizes text which contains substitutions, but no optional sections.
+fn tokenize_substitutions(text: &str) -> Result<Vec<Token>, String> {
+    split_placeholders(text)
+        .into_iter()
+        .map(|segment| match segment {
+            Segment::Text(text) => Ok(Token::Text(text.to_string())),
+            Segment::Placeholder(idx_str) => idx_str
+                .parse()
+                .map(Token::Parameter)
+                .map_err(|_| format!("{idx_str} is not a valid parameter index")),
+        })
+        .collect()
+}
+
+/// Byte offset of the brace closing the text, skipping over the brace pairs within it.
+fn closing_brace(text: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (offset, c) in text.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' if depth == 0 => return Some(offset),
+            '}' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+fn render_tokens(
+    tokens: &[Token],
+    parameters: &[impl AsRef<str>],
+    program: &mut String,
+) -> Result<(), ShellCommandRenderError> {
+    for token in tokens {
+        match token {
+            Token::Text(str) => {
+                program.push_str(str);
+            }
+            Token::Parameter(idx) => {
+                let value = parameters
+                    .get(*idx)
+                    .ok_or(ShellCommandRenderError::MissingParameter)?;
+
+                program.push_str(value.as_ref());
+            }
+            Token::Optional { parameter, body } => {
+                let value = parameters
+                    .get(*parameter)
+                    .ok_or(ShellCommandRenderError::MissingParameter)?;
+
+                if !value.as_ref().is_empty() {
+                    render_tokens(body, parameters, program)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn collect_parameters(tokens: &[Token], indexes: &mut BTreeSet<usize>) {
+    for token in tokens {
+        match token {
+            Token::Text(_) => {}
+            Token::Parameter(idx) => {
                 indexes.insert(*idx);
             }
+            Token::Optional { parameter, body } => {
+                indexes.insert(*parameter);
+                collect_parameters(body, indexes);
+            }
         }
-        indexes
     }
 }
 
@@ -216,6 +277,25 @@ mod shell_effect_template_tests {
         assert_eq!(program, "echo 'Hello baz, foo, and baz again!'")
     }
 
+    #[test]
+    fn optional_section_is_left_out_for_empty_value() {
+        let template = ShellCommandTemplate::new("ls{?0: --sort {0}} {1}").unwrap();
+        assert_eq!(template.get_required_parameters(), BTreeSet::from([0, 1]));
+
+        let Effect::ShellCommand { program, .. } = template.render(&["time", "/tmp"]).unwrap();
+        assert_eq!(program, "ls --sort time /tmp");
+
+        let Effect::ShellCommand { program, .. } = template.render(&["", "/tmp"]).unwrap();
+        assert_eq!(program, "ls /tmp");
+    }
+
+    #[test]
+    fn optional_section_must_be_closed() {
+        assert!(ShellCommandTemplate::new("ls{?0: --sort {0}").is_err());
+        assert!(ShellCommandTemplate::new("ls{?0 --sort}").is_err());
+        assert!(ShellCommandTemplate::new("ls{?zero: --sort {0}}").is_err());
+    }
+
     #[test]
     fn render_fails_when_parameters_are_missing() {
         let template_string = "echo 'Hello {0}!";
@@ -0,0 +1,127 @@
+/// How many of the most recent values are remembered per parameter.
+pub const HISTORY_LIMIT: usize = 50;
+
+/// Earlier values of a text input, browsed from the most recent towards the oldest.
+///
+/// The text typed before browsing is kept aside, and returned to once browsing past the most
+/// recent value.
+#[derive(Debug, Default)]
+pub struct InputHistory {
+    /// Values from the most recent to the oldest.
+    entries: Vec<String>,
+    /// Index of the value being shown, if browsing.
+    position: Option<usize>,
+    draft: String,
+}
+
+impl InputHistory {
+    /// The entries are expected from the most recent to the oldest.
+    #[must_use]
+    pub fn new(entries: Vec<String>) -> Self {
+        Self {
+            entries,
+            position: None,
+            draft: String::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+
+    #[must_use]
+    pub fn most_recent(&self) -> Option<&str> {
+        self.entries.first().map(String::as_str)
+    }
+
+    /// Steps to the next older value. The current input is kept aside when browsing begins.
+    /// Returns nothing if there are no older values.
+    pub fn older(&mut self, current_input: &str) -> Option<&str> {
+        let position = self.position.map_or(0, |position| position + 1);
+        if position >= self.entries.len() {
+            return None;
+        }
+
+        if self.position.is_none() {
+            self.draft = current_input.to_string();
+        }
+        self.position = Some(position);
+        Some(&self.entries[position])
+    }
+
+    /// Steps to the next more recent value, or back to the input kept aside. Returns nothing if
+    /// not browsing.
+    pub fn newer(&mut self) -> Option<&str> {
+        match self.position? {
+            0 => {
+                self.position = None;
+                Some(&self.draft)
+            }
+            position => {
+                self.position = Some(position - 1);
+                Some(&self.entries[position - 1])
+            }
+        }
+    }
+
+    /// Makes the value the most recent one, dropping its earlier occurrence and the values beyond
+    /// the limit.
+    pub fn record(&mut self, value: &str) {
+        self.entries.retain(|entry| entry != value);
+        self.entries.insert(0, value.to_string());
+        self.entries.truncate(HISTORY_LIMIT);
+        self.position = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history(entries: &[&str]) -> InputHistory {
+        InputHistory::new(entries.iter().map(ToString::to_string).collect())
+    }
+
+    #[test]
+    fn values_are_browsed_from_most_recent() {
+        let mut history = history(&["beta", "alpha"]);
+        assert_eq!(history.older("typed"), Some("beta"));
+        assert_eq!(history.older("beta"), Some("alpha"));
+        assert_eq!(history.older("alpha"), None);
+
+        assert_eq!(history.newer(), Some("beta"));
+        assert_eq!(history.newer(), Some("typed"));
+        assert_eq!(history.newer(), None);
+    }
+
+    #[test]
+    fn empty_history_cannot_be_browsed() {
+        let mut history = history(&[]);
+        assert_eq!(history.older(""), None);
+        assert_eq!(history.newer(), None);
+        assert_eq!(history.most_recent(), None);
+    }
+
+    #[test]
+    fn recorded_value_becomes_most_recent() {
+        let mut history = history(&["beta", "alpha"]);
+        history.record("alpha");
+        assert_eq!(history.entries(), ["alpha", "beta"]);
+        assert_eq!(history.most_recent(), Some("alpha"));
+    }
+
+    #[test]
+    fn oldest_values_are_dropped_beyond_limit() {
+        let mut history = history(&[]);
+        for i in 0..=HISTORY_LIMIT {
+            history.record(&i.to_string());
+        }
+        assert_eq!(history.entries().len(), HISTORY_LIMIT);
+        assert_eq!(
+            history.most_recent(),
+            Some(HISTORY_LIMIT.to_string().as_str())
+        );
+        assert!(!history.entries().contains(&"0".to_string()));
+    }
+}
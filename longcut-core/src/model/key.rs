@@ -5,6 +5,9 @@ use std::convert::TryFrom;
 pub struct Key {
     pub symbol: Symbol,
     pub modifiers: BTreeSet<Modifier>,
+    /// Whether the key is placed where the symbol is in the keyboard layout active on startup,
+    /// rather than where it is in the active keyboard layout.
+    pub physical: bool,
 }
 
 impl Key {
@@ -13,18 +16,28 @@ impl Key {
         Self {
             symbol,
             modifiers: BTreeSet::new(),
+            physical: false,
         }
     }
 
     pub fn add_modifier(&mut self, modifier: Modifier) {
         self.modifiers.insert(modifier);
     }
+
+    pub fn set_physical(&mut self, physical: bool) {
+        self.physical = physical;
+    }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
 pub enum Modifier {
     Alt,
+    /// The third level chooser of the keyboard layout, `ISO_Level3_Shift`.
+    AltGr,
     Control,
+    Hyper,
+    /// The third modifier, which the keyboard layouts seldom map any key to.
+    Mod3,
     Shift,
     Super,
 }
@@ -35,7 +48,10 @@ impl TryFrom<&str> for Modifier {
     fn try_from(value: &str) -> Result<Self, Self::Error> {
         match value {
             "alt" => Ok(Self::Alt),
+            "altgr" => Ok(Self::AltGr),
             "control" => Ok(Self::Control),
+            "hyper" => Ok(Self::Hyper),
+            "mod3" => Ok(Self::Mod3),
             "shift" => Ok(Self::Shift),
             "super" => Ok(Self::Super),
             _ => Err("value is not a valid symbol"),
@@ -53,6 +69,7 @@ pub enum Symbol {
     Break,
     CapsLock,
     Control,
+    Delete,
     Down,
     End,
     Return,
@@ -69,6 +86,18 @@ pub enum Symbol {
     F10,
     F11,
     F12,
+    F13,
+    F14,
+    F15,
+    F16,
+    F17,
+    F18,
+    F19,
+    F20,
+    F21,
+    F22,
+    F23,
+    F24,
     Fn,
     Home,
     Insert,
@@ -87,47 +116,134 @@ pub enum Symbol {
     SuperR,
     Tab,
     Up,
+    // Keypad keys
+    Keypad0,
+    Keypad1,
+    Keypad2,
+    Keypad3,
+    Keypad4,
+    Keypad5,
+    Keypad6,
+    Keypad7,
+    Keypad8,
+    Keypad9,
+    KeypadAdd,
+    KeypadDecimal,
+    KeypadDivide,
+    KeypadEnter,
+    KeypadEqual,
+    KeypadMultiply,
+    KeypadSubtract,
+    // Media and other special function keys
+    AudioLowerVolume,
+    AudioMicMute,
+    AudioMute,
+    AudioNext,
+    AudioPause,
+    AudioPlay,
+    AudioPrev,
+    AudioRaiseVolume,
+    AudioStop,
+    Calculator,
+    Eject,
+    Mail,
+    MonBrightnessDown,
+    MonBrightnessUp,
+    Search,
+    Sleep,
+    // Browser navigation keys
+    BrowserBack,
+    BrowserForward,
+    BrowserHome,
+    BrowserRefresh,
 }
 
+/// Function keys by their number, beginning from F1.
+const FUNCTION_KEYS: [Symbol; 24] = [
+    Symbol::F1,
+    Symbol::F2,
+    Symbol::F3,
+    Symbol::F4,
+    Symbol::F5,
+    Symbol::F6,
+    Symbol::F7,
+    Symbol::F8,
+    Symbol::F9,
+    Symbol::F10,
+    Symbol::F11,
+    Symbol::F12,
+    Symbol::F13,
+    Symbol::F14,
+    Symbol::F15,
+    Symbol::F16,
+    Symbol::F17,
+    Symbol::F18,
+    Symbol::F19,
+    Symbol::F20,
+    Symbol::F21,
+    Symbol::F22,
+    Symbol::F23,
+    Symbol::F24,
+];
+
 impl TryFrom<&str> for Symbol {
     type Error = &'static str;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        match value.to_lowercase().as_str() {
+        let name = value.to_lowercase();
+        // The number is taken as written, without a sign or leading zeros.
+        let function_key = name
+            .strip_prefix('f')
+            .filter(|number| {
+                !number.starts_with('0') && number.bytes().all(|byte| byte.is_ascii_digit())
+            })
+            .and_then(|number| number.parse::<usize>().ok())
+            .and_then(|number| FUNCTION_KEYS.get(number.checked_sub(1)?));
+        if let Some(symbol) = function_key {
+            return Ok(symbol.clone());
+        }
+
+        match name.as_str() {
             "alt_l" => Ok(Self::AltL),
             "alt_r" => Ok(Self::AltR),
             "backspace" => Ok(Self::BackSpace),
             "break" => Ok(Self::Break),
-            "capslock" => Ok(Self::CapsLock),
+            "capslock" | "caps_lock" => Ok(Self::CapsLock),
             "control_l" | "control_r" => Ok(Self::Control),
+            "delete" => Ok(Self::Delete),
             "down" => Ok(Self::Down),
             "end" => Ok(Self::End),
             "escape" => Ok(Self::Escape),
-            "f1" => Ok(Self::F1),
-            "f2" => Ok(Self::F2),
-            "f3" => Ok(Self::F3),
-            "f4" => Ok(Self::F4),
-            "f5" => Ok(Self::F5),
-            "f6" => Ok(Self::F6),
-            "f7" => Ok(Self::F7),
-            "f8" => Ok(Self::F8),
-            "f9" => Ok(Self::F9),
-            "f10" => Ok(Self::F10),
-            "f11" => Ok(Self::F11),
-            "f12" => Ok(Self::F12),
             "fn" => Ok(Self::Fn),
             "home" => Ok(Self::Home),
             "insert" => Ok(Self::Insert),
+            "kp_0" => Ok(Self::Keypad0),
+            "kp_1" => Ok(Self::Keypad1),
+            "kp_2" => Ok(Self::Keypad2),
+            "kp_3" => Ok(Self::Keypad3),
+            "kp_4" => Ok(Self::Keypad4),
+            "kp_5" => Ok(Self::Keypad5),
+            "kp_6" => Ok(Self::Keypad6),
+            "kp_7" => Ok(Self::Keypad7),
+            "kp_8" => Ok(Self::Keypad8),
+            "kp_9" => Ok(Self::Keypad9),
+            "kp_add" => Ok(Self::KeypadAdd),
+            "kp_decimal" => Ok(Self::KeypadDecimal),
+            "kp_divide" => Ok(Self::KeypadDivide),
+            "kp_enter" => Ok(Self::KeypadEnter),
+            "kp_equal" => Ok(Self::KeypadEqual),
+            "kp_multiply" => Ok(Self::KeypadMultiply),
+            "kp_subtract" => Ok(Self::KeypadSubtract),
             "left" => Ok(Self::Left),
             "menu" => Ok(Self::Menu),
-            "numlock" => Ok(Self::NumLock),
+            "numlock" | "num_lock" => Ok(Self::NumLock),
             "pagedown" => Ok(Self::PageDown),
             "pageup" => Ok(Self::PageUp),
             "pause" => Ok(Self::Pause),
-            "printscreen" => Ok(Self::PrintScreen),
+            "print" | "printscreen" => Ok(Self::PrintScreen),
             "right" => Ok(Self::Right),
             "enter" | "return" => Ok(Self::Return),
-            "scrolllock" => Ok(Self::ScrollLock),
+            "scrolllock" | "scroll_lock" => Ok(Self::ScrollLock),
             "shift_l" => Ok(Self::ShiftL),
             "shift_r" => Ok(Self::ShiftR),
             "space" => Ok(Self::Character(' ')),
@@ -135,6 +251,26 @@ impl TryFrom<&str> for Symbol {
             "super_r" => Ok(Self::SuperR),
             "tab" => Ok(Self::Tab),
             "up" => Ok(Self::Up),
+            "xf86audiolowervolume" | "audiolowervolume" => Ok(Self::AudioLowerVolume),
+            "xf86audiomicmute" | "audiomicmute" => Ok(Self::AudioMicMute),
+            "xf86audiomute" | "audiomute" => Ok(Self::AudioMute),
+            "xf86audionext" | "audionext" => Ok(Self::AudioNext),
+            "xf86audiopause" | "audiopause" => Ok(Self::AudioPause),
+            "xf86audioplay" | "audioplay" => Ok(Self::AudioPlay),
+            "xf86audioprev" | "audioprev" => Ok(Self::AudioPrev),
+            "xf86audioraisevolume" | "audioraisevolume" => Ok(Self::AudioRaiseVolume),
+            "xf86audiostop" | "audiostop" => Ok(Self::AudioStop),
+            "xf86calculator" | "calculator" => Ok(Self::Calculator),
+            "xf86eject" | "eject" => Ok(Self::Eject),
+            "xf86mail" | "mail" => Ok(Self::Mail),
+            "xf86monbrightnessdown" | "monbrightnessdown" => Ok(Self::MonBrightnessDown),
+            "xf86monbrightnessup" | "monbrightnessup" => Ok(Self::MonBrightnessUp),
+            "xf86search" | "search" => Ok(Self::Search),
+            "xf86sleep" | "sleep" => Ok(Self::Sleep),
+            "xf86back" => Ok(Self::BrowserBack),
+            "xf86forward" => Ok(Self::BrowserForward),
+            "xf86homepage" => Ok(Self::BrowserHome),
+            "xf86reload" => Ok(Self::BrowserRefresh),
             _ => {
                 if value.chars().count() == 1 {
                     Ok(Self::Character(value.chars().next().unwrap()))
@@ -146,3 +282,29 @@ impl TryFrom<&str> for Symbol {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn function_keys_are_parsed_by_their_number() {
+        assert_eq!(Symbol::try_from("F1"), Ok(Symbol::F1));
+        assert_eq!(Symbol::try_from("f12"), Ok(Symbol::F12));
+        assert_eq!(Symbol::try_from("F24"), Ok(Symbol::F24));
+    }
+
+    #[test]
+    fn function_key_numbers_must_be_plain_digits() {
+        assert!(Symbol::try_from("f01").is_err());
+        assert!(Symbol::try_from("f+1").is_err());
+        assert!(Symbol::try_from("f0").is_err());
+        assert!(Symbol::try_from("f25").is_err());
+        assert!(Symbol::try_from("f 1").is_err());
+    }
+
+    #[test]
+    fn single_f_is_a_character() {
+        assert_eq!(Symbol::try_from("f"), Ok(Symbol::Character('f')));
+    }
+}
@@ -6,6 +6,8 @@ use crate::model::shortcut_map::ShortcutMap;
 pub enum Action {
     Branch(Layer),
     Execute(Command),
+    /// Shortcuts continuing a multi-key shortcut, once its earlier keys have been pressed.
+    Sequence(ShortcutMap<Action>),
 }
 
 #[derive(Debug)]
@@ -23,23 +25,140 @@ impl Layer {
         }
     }
 
+    /// Shortcuts made up of several keys are pressed one key after another.
+    ///
     /// # Errors
     ///
-    /// Returns the key and action if the shortcut is already assigned.
-    pub fn add_command(&mut self, shortcut: Key, command: Command) -> Result<(), (Key, Action)> {
-        self.shortcuts
-            .try_assign(shortcut, Action::Execute(command))
+    /// Returns the keys and action if the shortcut is already assigned, if it begins with another
+    /// shortcut, or if another shortcut begins with it.
+    pub fn add_command(
+        &mut self,
+        shortcut: Vec<Key>,
+        command: Command,
+    ) -> Result<(), (Vec<Key>, Action)> {
+        self.add_action(shortcut, Action::Execute(command))
     }
 
+    /// Shortcuts made up of several keys are pressed one key after another.
+    ///
     /// # Errors
     ///
-    /// Returns the key and action if the shortcut is already assigned.
-    pub fn add_layer(&mut self, shortcut: Key, layer: Layer) -> Result<(), (Key, Action)> {
-        self.shortcuts.try_assign(shortcut, Action::Branch(layer))
+    /// Returns the keys and action if the shortcut is already assigned, if it begins with another
+    /// shortcut, or if another shortcut begins with it.
+    pub fn add_layer(
+        &mut self,
+        shortcut: Vec<Key>,
+        layer: Layer,
+    ) -> Result<(), (Vec<Key>, Action)> {
+        self.add_action(shortcut, Action::Branch(layer))
     }
 
+    /// Shortcuts continuing the keys pressed so far, or nothing if no shortcut begins with them.
     #[must_use]
-    pub fn resolve_shortcut(&self, key: &Key) -> Option<&Action> {
-        self.shortcuts.match_fuzzy(key)
+    pub fn continuations(&self, pressed: &[Key]) -> Option<&ShortcutMap<Action>> {
+        pressed.iter().try_fold(&self.shortcuts, |shortcuts, key| {
+            match shortcuts.match_fuzzy(key)? {
+                Action::Sequence(continuations) => Some(continuations),
+                Action::Branch(_) | Action::Execute(_) => None,
+            }
+        })
+    }
+
+    /// Resolves the key pressed after the earlier keys of a multi-key shortcut.
+    #[must_use]
+    pub fn resolve_shortcut(&self, pressed: &[Key], key: &Key) -> Option<&Action> {
+        self.continuations(pressed)?.match_fuzzy(key)
+    }
+
+    fn add_action(&mut self, shortcut: Vec<Key>, action: Action) -> Result<(), (Vec<Key>, Action)> {
+        let Some((last, prefix)) = shortcut.split_last() else {
+            return Err((shortcut, action));
+        };
+
+        // The earlier keys form an implicit prefix tree, whose nodes are shared by the shortcuts
+        // beginning with the same keys.
+        let mut shortcuts = &mut self.shortcuts;
+        for key in prefix {
+            let node =
+                shortcuts.get_or_assign(key.clone(), || Action::Sequence(ShortcutMap::new()));
+            let Action::Sequence(continuations) = node else {
+                return Err((shortcut, action));
+            };
+            shortcuts = continuations;
+        }
+
+        match shortcuts.try_assign(last.clone(), action) {
+            Ok(()) => Ok(()),
+            Err((_, action)) => Err((shortcut, action)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::key::Symbol;
+
+    fn keys(characters: &str) -> Vec<Key> {
+        characters
+            .chars()
+            .map(|c| Key::new(Symbol::Character(c)))
+            .collect()
+    }
+
+    fn layer_name(action: Option<&Action>) -> Option<&str> {
+        match action? {
+            Action::Branch(layer) => Some(&layer.name),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn multi_key_shortcuts_share_their_prefix() {
+        let mut root = Layer::new("Root".into());
+        root.add_layer(keys("gg"), Layer::new("Top".into()))
+            .unwrap();
+        root.add_layer(keys("gb"), Layer::new("Bottom".into()))
+            .unwrap();
+
+        assert!(matches!(
+            root.resolve_shortcut(&[], &keys("g")[0]),
+            Some(Action::Sequence(_))
+        ));
+        assert_eq!(root.continuations(&keys("g")).unwrap().len(), 2);
+        assert_eq!(
+            layer_name(root.resolve_shortcut(&keys("g"), &keys("b")[0])),
+            Some("Bottom")
+        );
+        assert!(root.resolve_shortcut(&keys("g"), &keys("x")[0]).is_none());
+    }
+
+    #[test]
+    fn shortcut_cannot_begin_with_another_shortcut() {
+        let mut root = Layer::new("Root".into());
+        root.add_layer(keys("g"), Layer::new("Go".into())).unwrap();
+
+        assert!(
+            root.add_layer(keys("gg"), Layer::new("Top".into()))
+                .is_err()
+        );
+        assert!(root.continuations(&keys("g")).is_none());
+    }
+
+    #[test]
+    fn shortcut_cannot_be_a_prefix_of_another_shortcut() {
+        let mut root = Layer::new("Root".into());
+        root.add_layer(keys("gg"), Layer::new("Top".into()))
+            .unwrap();
+
+        assert!(root.add_layer(keys("g"), Layer::new("Go".into())).is_err());
+        assert!(
+            root.add_layer(keys("gg"), Layer::new("Top".into()))
+                .is_err()
+        );
+        assert!(
+            root.add_layer(Vec::new(), Layer::new("None".into()))
+                .is_err()
+        );
     }
 }
@@ -1,7 +1,18 @@
+pub mod activation;
 pub mod command;
+pub mod completion;
 pub mod effect;
 pub mod event;
+pub mod input_history;
 pub mod key;
 pub mod layer;
+pub mod option_list;
+pub mod option_source;
+pub mod options_command;
 pub mod parameter;
+pub mod placeholder;
+pub mod selection;
 pub mod shortcut_map;
+pub mod text_buffer;
+pub mod timed_cache;
+pub mod window;
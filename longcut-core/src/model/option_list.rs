@@ -0,0 +1,195 @@
+/// A filterable list of options, split into fixed-size pages.
+///
+/// Only a single page of the options is accessible at a time. The filter narrows the list down to
+/// the options which contain the filter string, ignoring case. Changing the filter always returns
+/// the list back to its first page.
+#[derive(Debug)]
+pub struct OptionList<T> {
+    options: Vec<T>,
+    filter: String,
+    page_size: usize,
+    page_index: usize,
+}
+
+impl<T: AsRef<str>> OptionList<T> {
+    /// Page size is clamped to be at least one.
+    #[must_use]
+    pub fn new(options: Vec<T>, page_size: usize) -> Self {
+        Self {
+            options,
+            filter: String::new(),
+            page_size: page_size.max(1),
+            page_index: 0,
+        }
+    }
+
+    /// Returns all the options, regardless of the filter.
+    #[must_use]
+    pub fn options(&self) -> &[T] {
+        &self.options
+    }
+
+    /// Replaces all the options, keeping the filter. The current page is kept as well, unless the
+    /// new options no longer reach that far.
+    pub fn set_options(&mut self, options: Vec<T>) {
+        self.options = options;
+        self.page_index = self.page_index.min(self.page_count() - 1);
+    }
+
+    #[must_use]
+    pub fn filter(&self) -> &str {
+        &self.filter
+    }
+
+    pub fn push_filter(&mut self, c: char) {
+        self.filter.push(c);
+        self.page_index = 0;
+    }
+
+    /// Removes the last character of the filter, returning it if the filter was not empty.
+    pub fn pop_filter(&mut self) -> Option<char> {
+        self.page_index = 0;
+        self.filter.pop()
+    }
+
+    pub fn clear_filter(&mut self) {
+        self.filter.clear();
+        self.page_index = 0;
+    }
+
+    /// Iterates over the options matching the current filter.
+    pub fn matching(&self) -> impl Iterator<Item = &T> {
+        let filter = self.filter.to_lowercase();
+        self.options
+            .iter()
+            .filter(move |option| option.as_ref().to_lowercase().contains(&filter))
+    }
+
+    #[must_use]
+    pub fn matching_count(&self) -> usize {
+        self.matching().count()
+    }
+
+    /// Number of pages the matching options span. Even an empty list has a single, empty page.
+    #[must_use]
+    pub fn page_count(&self) -> usize {
+        self.matching_count().div_ceil(self.page_size).max(1)
+    }
+
+    /// Index of the first option of the current page within the matching options.
+    #[must_use]
+    pub fn page_offset(&self) -> usize {
+        self.page_index * self.page_size
+    }
+
+    /// Returns the options on the currently active page.
+    #[must_use]
+    pub fn current_page(&self) -> Vec<&T> {
+        self.matching()
+            .skip(self.page_offset())
+            .take(self.page_size)
+            .collect()
+    }
+
+    /// Moves onto the next page. Returns false if already on the last page.
+    pub fn next_page(&mut self) -> bool {
+        if self.page_index + 1 < self.page_count() {
+            self.page_index += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Moves onto the previous page. Returns false if already on the first page.
+    pub fn previous_page(&mut self) -> bool {
+        if self.page_index > 0 {
+            self.page_index -= 1;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbered_options(count: usize) -> Vec<String> {
+        (1..=count).map(|n| format!("option {n}")).collect()
+    }
+
+    #[test]
+    fn options_are_split_into_pages() {
+        let mut list = OptionList::new(numbered_options(5), 2);
+        assert_eq!(list.page_count(), 3);
+        assert_eq!(list.current_page(), vec!["option 1", "option 2"]);
+
+        assert!(list.next_page());
+        assert_eq!(list.page_offset(), 2);
+        assert_eq!(list.current_page(), vec!["option 3", "option 4"]);
+
+        assert!(list.next_page());
+        assert_eq!(list.current_page(), vec!["option 5"]);
+    }
+
+    #[test]
+    fn paging_stops_at_both_ends() {
+        let mut list = OptionList::new(numbered_options(3), 2);
+        assert!(!list.previous_page());
+        assert!(list.next_page());
+        assert!(!list.next_page());
+        assert!(list.previous_page());
+        assert_eq!(list.current_page(), vec!["option 1", "option 2"]);
+    }
+
+    #[test]
+    fn empty_list_has_a_single_empty_page() {
+        let list = OptionList::<String>::new(vec![], 10);
+        assert_eq!(list.page_count(), 1);
+        assert!(list.current_page().is_empty());
+    }
+
+    #[test]
+    fn replacing_options_keeps_the_page_when_possible() {
+        let mut list = OptionList::new(numbered_options(5), 2);
+        assert!(list.next_page());
+
+        list.set_options(numbered_options(6));
+        assert_eq!(list.page_offset(), 2);
+
+        list.set_options(numbered_options(1));
+        assert_eq!(list.page_offset(), 0);
+        assert_eq!(list.current_page(), vec!["option 1"]);
+    }
+
+    #[test]
+    fn filter_narrows_down_options_ignoring_case() {
+        let mut list = OptionList::new(vec!["Firefox", "Spotify", "firewall"], 10);
+        list.push_filter('F');
+        list.push_filter('i');
+        list.push_filter('r');
+        assert_eq!(list.matching_count(), 2);
+        assert_eq!(list.current_page(), vec![&"Firefox", &"firewall"]);
+
+        assert_eq!(list.pop_filter(), Some('r'));
+        assert_eq!(list.matching_count(), 2);
+
+        list.clear_filter();
+        assert_eq!(list.matching_count(), 3);
+        assert_eq!(list.pop_filter(), None);
+    }
+
+    #[test]
+    fn changing_the_filter_returns_to_the_first_page() {
+        let mut list = OptionList::new(numbered_options(12), 5);
+        assert!(list.next_page());
+        list.push_filter('1');
+        assert_eq!(list.page_offset(), 0);
+        assert_eq!(
+            list.current_page(),
+            vec!["option 1", "option 10", "option 11", "option 12"]
+        );
+    }
+}
@@ -0,0 +1,274 @@
+use crate::model::options_command::OptionsCommandTemplate;
+use crate::model::window::{ClientWindow, WindowField};
+use regex::Regex;
+use serde_json::Value;
+use std::path::Path;
+
+/// A single option of a choose parameter. The label is shown to the user, while the value is what
+/// the parameter is substituted with.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ChooseOption {
+    pub label: String,
+    pub value: String,
+}
+
+impl ChooseOption {
+    /// Option which is shown as its value.
+    #[must_use]
+    pub fn new(value: String) -> Self {
+        Self {
+            label: value.clone(),
+            value,
+        }
+    }
+
+    #[must_use]
+    pub fn labeled(label: String, value: String) -> Self {
+        Self { label, value }
+    }
+}
+
+impl AsRef<str> for ChooseOption {
+    fn as_ref(&self) -> &str {
+        &self.label
+    }
+}
+
+/// Where the options of a choose parameter are generated from.
+#[derive(Debug)]
+pub enum OptionSource {
+    /// Output of a shell command.
+    Command {
+        command: OptionsCommandTemplate,
+        format: OutputFormat,
+    },
+    /// Contents of a file.
+    File { path: String, format: OutputFormat },
+    /// Paths matching a glob pattern. The options are labeled with the file names.
+    Glob { pattern: String },
+    /// Open windows. The options are labeled with the class and the title of the windows.
+    Windows { field: WindowField },
+}
+
+/// How the text of an option source is turned into options.
+#[derive(Debug)]
+pub enum OutputFormat {
+    /// Every non-empty piece of text between the separators is an option.
+    Split(Separator),
+    Json(JsonSelector),
+}
+
+#[derive(Debug)]
+pub enum Separator {
+    Literal(String),
+    Pattern(Regex),
+}
+
+impl OutputFormat {
+    /// # Errors
+    ///
+    /// Returns an error if the text does not match the expected format.
+    pub fn parse(&self, text: &str) -> Result<Vec<ChooseOption>, String> {
+        match self {
+            OutputFormat::Split(separator) => {
+                let pieces: Vec<&str> = match separator {
+                    Separator::Literal(separator) => text.split(separator.as_str()).collect(),
+                    Separator::Pattern(pattern) => pattern.split(text).collect(),
+                };
+
+                Ok(pieces
+                    .into_iter()
+                    .map(str::trim)
+                    .filter(|piece| !piece.is_empty())
+                    .map(|piece| ChooseOption::new(piece.to_string()))
+                    .collect())
+            }
+            OutputFormat::Json(selector) => selector.select(text),
+        }
+    }
+}
+
+/// Selects options out of a JSON document.
+///
+/// The `items` path points to an array within the document, and the `label` and `value` paths
+/// point to the fields of each array item. Paths are made up of object keys and array indexes
+/// separated by dots, such as `items.0.metadata.name`. An empty path refers to the value itself.
+#[derive(Debug)]
+pub struct JsonSelector {
+    items: Vec<String>,
+    label: Vec<String>,
+    value: Option<Vec<String>>,
+}
+
+impl JsonSelector {
+    /// The value defaults to the label if no value path is provided.
+    #[must_use]
+    pub fn new(items: &str, label: &str, value: Option<&str>) -> Self {
+        Self {
+            items: parse_path(items),
+            label: parse_path(label),
+            value: value.map(parse_path),
+        }
+    }
+
+    /// Items which lack a label or a value, or which point to something other than a string,
+    /// number or boolean, are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the text is not valid JSON, or if the items path does not point to an
+    /// array.
+    pub fn select(&self, text: &str) -> Result<Vec<ChooseOption>, String> {
+        let document: Value =
+            serde_json::from_str(text).map_err(|e| format!("Invalid JSON output: {e}"))?;
+
+        let Some(Value::Array(items)) = lookup(&document, &self.items) else {
+            return Err(format!(
+                "JSON output has no array at '{}'",
+                self.items.join(".")
+            ));
+        };
+
+        Ok(items
+            .iter()
+            .filter_map(|item| {
+                let label = lookup(item, &self.label).and_then(as_text)?;
+                let value = match &self.value {
+                    Some(path) => lookup(item, path).and_then(as_text)?,
+                    None => label.clone(),
+                };
+                Some(ChooseOption::labeled(label, value))
+            })
+            .collect())
+    }
+}
+
+/// Turns the paths into options labeled with their file names.
+#[must_use]
+pub fn path_options(paths: Vec<String>) -> Vec<ChooseOption> {
+    paths
+        .into_iter()
+        .map(|path| {
+            let label = Path::new(&path)
+                .file_name()
+                .map_or_else(|| path.clone(), |name| name.to_string_lossy().into_owned());
+            ChooseOption::labeled(label, path)
+        })
+        .collect()
+}
+
+/// Turns the windows into options labeled with their classes and titles, whose values are the
+/// given field of the window.
+#[must_use]
+pub fn window_options(windows: Vec<ClientWindow>, field: WindowField) -> Vec<ChooseOption> {
+    windows
+        .into_iter()
+        .map(|window| {
+            let label = format!("{}: {}", window.class, window.title);
+            ChooseOption::labeled(label, window.field(field))
+        })
+        .collect()
+}
+
+fn parse_path(path: &str) -> Vec<String> {
+    path.split('.')
+        .filter(|segment| !segment.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn lookup<'a>(value: &'a Value, path: &[String]) -> Option<&'a Value> {
+    path.iter().try_fold(value, |value, segment| match value {
+        Value::Array(items) => items.get(segment.parse::<usize>().ok()?),
+        Value::Object(fields) => fields.get(segment),
+        _ => None,
+    })
+}
+
+fn as_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => Some(text.clone()),
+        Value::Number(number) => Some(number.to_string()),
+        Value::Bool(boolean) => Some(boolean.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(options: &[ChooseOption]) -> Vec<&str> {
+        options.iter().map(|option| option.value.as_str()).collect()
+    }
+
+    #[test]
+    fn text_is_split_by_literal_separator() {
+        let format = OutputFormat::Split(Separator::Literal(",".to_string()));
+        let options = format.parse("alpha, beta,,gamma\n").unwrap();
+        assert_eq!(values(&options), vec!["alpha", "beta", "gamma"]);
+    }
+
+    #[test]
+    fn text_is_split_by_regex_separator() {
+        let format = OutputFormat::Split(Separator::Pattern(Regex::new(r"\s+").unwrap()));
+        let options = format.parse("alpha  beta\tgamma\n").unwrap();
+        assert_eq!(values(&options), vec!["alpha", "beta", "gamma"]);
+    }
+
+    #[test]
+    fn json_items_are_selected_by_path() {
+        let output = r#"{"items": [
+            {"metadata": {"name": "default", "uid": 1}},
+            {"metadata": {"name": "kube-system", "uid": 2}},
+            {"metadata": {}}
+        ]}"#;
+        let selector = JsonSelector::new("items", "metadata.name", Some("metadata.uid"));
+        let options = selector.select(output).unwrap();
+
+        assert_eq!(
+            options,
+            vec![
+                ChooseOption::labeled("default".to_string(), "1".to_string()),
+                ChooseOption::labeled("kube-system".to_string(), "2".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn json_value_defaults_to_label() {
+        let selector = JsonSelector::new("", "0", None);
+        let options = selector.select(r#"[["a", "x"], ["b", "y"]]"#).unwrap();
+        assert_eq!(values(&options), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn json_items_must_be_an_array() {
+        let selector = JsonSelector::new("items", "name", None);
+        assert!(selector.select(r#"{"items": {}}"#).is_err());
+        assert!(selector.select("not json").is_err());
+    }
+
+    #[test]
+    fn paths_are_labeled_with_file_names() {
+        let options = path_options(vec!["/home/user/projects/longcut".to_string()]);
+        assert_eq!(options[0].label, "longcut");
+        assert_eq!(options[0].value, "/home/user/projects/longcut");
+    }
+
+    #[test]
+    fn windows_are_labeled_with_class_and_title() {
+        let window = ClientWindow {
+            id: 6_291_459,
+            class: "firefox".to_string(),
+            title: "Mozilla Firefox".to_string(),
+        };
+
+        let options = window_options(vec![window.clone()], WindowField::Id);
+        assert_eq!(options[0].label, "firefox: Mozilla Firefox");
+        assert_eq!(options[0].value, "6291459");
+
+        let options = window_options(vec![window], WindowField::Title);
+        assert_eq!(options[0].value, "Mozilla Firefox");
+    }
+}
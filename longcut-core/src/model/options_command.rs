@@ -0,0 +1,173 @@
+use crate::model::effect::ShellCommandRenderError;
+use crate::model::placeholder::{Segment, split_placeholders};
+use std::collections::BTreeSet;
+
+#[derive(Debug)]
+enum Token {
+    Text(String),
+    Parameter(usize),
+    /// Placeholder naming a parameter, until resolved into its index.
+    Name(String),
+}
+
+/// A shell command generating the options of a choose parameter.
+///
+/// The command may refer to the values of other parameters either by index, `{0}`, or by name,
+/// `{Parameter name}`. Braces which cannot enclose a parameter reference are kept as literal text,
+/// allowing commands such as `awk '{ print $2 }'` or `echo ${HOME}` to be used unchanged: the
+/// braced text begins or ends with whitespace, contains characters other than letters, digits,
+/// spaces, `_` and `-`, or follows a `$`.
+#[derive(Debug)]
+pub struct OptionsCommandTemplate {
+    tokens: Vec<Token>,
+}
+
+impl OptionsCommandTemplate {
+    /// # Errors
+    ///
+    /// Returns an error if the command string is empty.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the regex pattern for parameter placeholders fails to compile.
+    pub fn new(command: &str) -> Result<Self, String> {
+        if command.is_empty() {
+            return Err("command must not be an empty string".into());
+        }
+
+        let mut tokens: Vec<Token> = Vec::new();
+        let mut follows_dollar = false;
+        for segment in split_placeholders(command) {
+            let token = match segment {
+                Segment::Text(text) => {
+                    follows_dollar = text.ends_with('$');
+                    tokens.push(Token::Text(text.to_string()));
+                    continue;
+                }
+                Segment::Placeholder(content) if follows_dollar || !is_reference(content) => {
+                    Token::Text(format!("{{{content}}}"))
+                }
+                Segment::Placeholder(content) => match content.parse() {
+                    Ok(idx) => Token::Parameter(idx),
+                    Err(_) => Token::Name(content.to_string()),
+                },
+            };
+            follows_dollar = false;
+            tokens.push(token);
+        }
+
+        Ok(Self { tokens })
+    }
+
+    /// Turns the placeholders naming the parameters into index placeholders. When several
+    /// parameters share the same name, the first one is referred to.
+    ///
+    /// # Errors
+    ///
+    /// Returns the name of a placeholder if none of the parameters has the name.
+    pub fn resolve_names(&mut self, parameter_names: &[impl AsRef<str>]) -> Result<(), String> {
+        for token in &mut self.tokens {
+            if let Token::Name(name) = token {
+                let idx = parameter_names
+                    .iter()
+                    .position(|p| p.as_ref() == name)
+                    .ok_or_else(|| name.clone())?;
+                *token = Token::Parameter(idx);
+            }
+        }
+        Ok(())
+    }
+
+    /// # Errors
+    ///
+    /// Returns an error if a referenced parameter value is missing, or a parameter name is left
+    /// unresolved.
+    pub fn render(
+        &self,
+        parameters: &[impl AsRef<str>],
+    ) -> Result<String, ShellCommandRenderError> {
+        let mut command = String::new();
+        for token in &self.tokens {
+            match token {
+                Token::Text(text) => command.push_str(text),
+                Token::Parameter(idx) => {
+                    let value = parameters
+                        .get(*idx)
+                        .ok_or(ShellCommandRenderError::MissingParameter)?;
+                    command.push_str(value.as_ref());
+                }
+                Token::Name(_) => return Err(ShellCommandRenderError::MissingParameter),
+            }
+        }
+        Ok(command)
+    }
+
+    #[must_use]
+    pub fn get_required_parameters(&self) -> BTreeSet<usize> {
+        let mut indexes = BTreeSet::new();
+        for token in &self.tokens {
+            if let Token::Parameter(idx) = token {
+                indexes.insert(*idx);
+            }
+        }
+        indexes
+    }
+}
+
+/// Whether the braced text can refer to a parameter, by its index or name.
+fn is_reference(content: &str) -> bool {
+    !content.is_empty()
+        && content.trim() == content
+        && content
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, ' ' | '_' | '-'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_string_is_not_allowed() {
+        assert!(OptionsCommandTemplate::new("").is_err());
+    }
+
+    #[test]
+    fn unrelated_braces_are_kept_as_is() {
+        let no_parameters: Vec<String> = vec![];
+        for command in [
+            "pactl list sinks | awk '{ print $2 }'",
+            "xrandr | awk '{print$1}'",
+            "ls ${HOME}",
+        ] {
+            let mut template = OptionsCommandTemplate::new(command).unwrap();
+            template.resolve_names(&["Sink"]).unwrap();
+            assert!(template.get_required_parameters().is_empty());
+            assert_eq!(template.render(&no_parameters).unwrap(), command);
+        }
+    }
+
+    #[test]
+    fn parameters_are_referred_to_by_index_and_name() {
+        let command = "kubectl --context {0} get ns -l team={Team name}";
+        let mut template = OptionsCommandTemplate::new(command).unwrap();
+        template.resolve_names(&["Context", "Team name"]).unwrap();
+        assert_eq!(template.get_required_parameters(), BTreeSet::from([0, 1]));
+
+        let rendered = template.render(&["prod", "core"]).unwrap();
+        assert_eq!(rendered, "kubectl --context prod get ns -l team=core");
+    }
+
+    #[test]
+    fn unknown_names_are_rejected() {
+        let mut template = OptionsCommandTemplate::new("ls ~/{Projct}").unwrap();
+        let result = template.resolve_names(&["Project"]);
+        assert_eq!(result, Err("Projct".to_string()));
+    }
+
+    #[test]
+    fn render_fails_when_parameters_are_missing() {
+        let template = OptionsCommandTemplate::new("ls {1}").unwrap();
+        assert!(template.render(&["only one"]).is_err());
+    }
+}
@@ -1,4 +1,10 @@
+use crate::model::option_source::{ChooseOption, OptionSource};
+use crate::model::options_command::OptionsCommandTemplate;
+use crate::model::selection::Selection;
+use regex::Regex;
 use std::fmt::Debug;
+use std::path::Path;
+use std::time::Duration;
 
 // ----------------------------------------------------------------------------
 // Variants for Parameter definitions and values.
@@ -8,6 +14,8 @@ use std::fmt::Debug;
 pub enum ParameterDefinitionVariant {
     Character(CharacterParameter),
     Choose(ChooseParameter),
+    Path(PathParameter),
+    Secret(SecretParameter),
     Text(TextParameter),
 }
 
@@ -15,7 +23,57 @@ pub enum ParameterDefinitionVariant {
 pub enum ParameterValueVariant {
     Character(ParameterValue<CharacterParameter>),
     Choose(ParameterValue<ChooseParameter>),
+    Path(ParameterValue<PathParameter>),
+    Secret(ParameterValue<SecretParameter>),
     Text(ParameterValue<TextParameter>),
+    /// The input of an optional parameter was skipped, leaving it to its default value.
+    Default(String),
+}
+
+impl ParameterDefinitionVariant {
+    /// Checks whether the text is a valid value for the parameter.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error describing why the value is not valid.
+    pub fn check_value(&self, value: &str) -> Result<(), String> {
+        match self {
+            ParameterDefinitionVariant::Character(parameter) => {
+                let mut chars = value.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => parameter.try_assign_value(c).map(drop),
+                    _ => Err("Must be a single character".into()),
+                }
+            }
+            ParameterDefinitionVariant::Choose(parameter) => {
+                parameter.try_assign_value(value).map(drop)
+            }
+            ParameterDefinitionVariant::Path(parameter) => {
+                parameter.try_assign_value(value).map(drop)
+            }
+            ParameterDefinitionVariant::Secret(parameter) => {
+                parameter.try_assign_value(value.to_string()).map(drop)
+            }
+            ParameterDefinitionVariant::Text(parameter) => {
+                parameter.try_assign_value(value).map(drop)
+            }
+        }
+    }
+}
+
+impl ParameterValueVariant {
+    /// Returns the string which the value is substituted with in command templates.
+    #[must_use]
+    pub fn to_substitution_string(&self) -> String {
+        match self {
+            ParameterValueVariant::Character(value) => value.get().to_string(),
+            ParameterValueVariant::Choose(value) => value.get().clone(),
+            ParameterValueVariant::Path(value) => value.get().clone(),
+            ParameterValueVariant::Secret(value) => value.get().expose().to_string(),
+            ParameterValueVariant::Text(value) => value.get().clone(),
+            ParameterValueVariant::Default(value) => value.clone(),
+        }
+    }
 }
 
 // ----------------------------------------------------------------------------
@@ -34,7 +92,7 @@ pub trait Parameter: Sized {
     fn try_assign_value(
         &self,
         value: impl Into<Self::Value>,
-    ) -> Result<ParameterValue<Self>, &'static str>;
+    ) -> Result<ParameterValue<Self>, String>;
 }
 
 /// A value which has been assigned to a [Parameter].
@@ -48,6 +106,10 @@ impl<P: Parameter> ParameterValue<P> {
     pub fn take(self) -> P::Value {
         self.0
     }
+
+    pub fn get(&self) -> &P::Value {
+        &self.0
+    }
 }
 
 /// A single character.
@@ -60,14 +122,44 @@ impl Parameter for CharacterParameter {
     fn try_assign_value(
         &self,
         value: impl Into<Self::Value>,
-    ) -> Result<ParameterValue<Self>, &'static str> {
+    ) -> Result<ParameterValue<Self>, String> {
         Ok(ParameterValue(value.into()))
     }
 }
 
 /// A piece of text, a string.
 #[derive(Debug)]
-pub struct TextParameter;
+pub struct TextParameter {
+    /// Selection whose text the input is prefilled with.
+    pub default_from: Option<Selection>,
+    /// Whether the entered values are remembered, so that they can be entered again.
+    pub history: bool,
+    /// Pattern which the value must contain a match of. An empty value skips the check, unless
+    /// the parameter is required.
+    pub pattern: Option<Regex>,
+    /// Bounds for the number of characters in the value. An empty value skips the minimum length
+    /// check, unless the parameter is required.
+    pub min_length: Option<usize>,
+    pub max_length: Option<usize>,
+    /// Whether an empty value is rejected.
+    pub required: bool,
+    /// Command whose output lines are offered as completions of the typed text.
+    pub complete: Option<OptionsCommandTemplate>,
+}
+
+impl Default for TextParameter {
+    fn default() -> Self {
+        Self {
+            default_from: None,
+            history: true,
+            pattern: None,
+            min_length: None,
+            max_length: None,
+            required: false,
+            complete: None,
+        }
+    }
+}
 
 impl Parameter for TextParameter {
     type Value = String;
@@ -75,36 +167,114 @@ impl Parameter for TextParameter {
     fn try_assign_value(
         &self,
         value: impl Into<Self::Value>,
-    ) -> Result<ParameterValue<Self>, &'static str> {
+    ) -> Result<ParameterValue<Self>, String> {
+        let into_value = value.into();
+        let length = into_value.chars().count();
+
+        // An optional value may be left empty, regardless of the constraints on its content.
+        if into_value.is_empty() {
+            return if self.required {
+                Err("A value is required".into())
+            } else {
+                Ok(ParameterValue(into_value))
+            };
+        }
+
+        if let Some(min_length) = self.min_length
+            && length < min_length
+        {
+            return Err(format!("Must be at least {min_length} characters long"));
+        }
+
+        if let Some(max_length) = self.max_length
+            && length > max_length
+        {
+            return Err(format!("Must be at most {max_length} characters long"));
+        }
+
+        if let Some(pattern) = &self.pattern
+            && !pattern.is_match(&into_value)
+        {
+            return Err(format!("Must match the pattern {pattern}"));
+        }
+
+        Ok(ParameterValue(into_value))
+    }
+}
+
+/// A piece of text which is not shown, such as a password or a token.
+#[derive(Debug, Default)]
+pub struct SecretParameter {
+    /// Command whose output is used as the value, instead of asking the user for it.
+    pub command: Option<OptionsCommandTemplate>,
+}
+
+impl Parameter for SecretParameter {
+    type Value = SecretText;
+
+    fn try_assign_value(
+        &self,
+        value: impl Into<Self::Value>,
+    ) -> Result<ParameterValue<Self>, String> {
         Ok(ParameterValue(value.into()))
     }
 }
 
+/// Text whose debug output is redacted, so that it is not written into logs by accident.
+#[derive(Clone, PartialEq, Eq)]
+pub struct SecretText(String);
+
+impl SecretText {
+    #[must_use]
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for SecretText {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl Debug for SecretText {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SecretText(<redacted>)")
+    }
+}
+
 /// A list of pre-defined options to choose from.
 #[derive(Debug)]
 pub struct ChooseParameter {
-    pub options: Vec<String>,
-    pub gen_options_command: Option<String>,
-    pub gen_options_split_by: String,
+    pub options: Vec<ChooseOption>,
+    /// Source generating more options.
+    pub gen_options_source: Option<OptionSource>,
+    /// How long the generated options are reused before generating them again. Without a
+    /// duration, the options are generated anew each time.
+    pub gen_options_cache_ttl: Option<Duration>,
 }
 
 impl ChooseParameter {
     /// # Errors
     ///
-    /// Returns an error if neither `options` nor `gen_options_command` is provided.
+    /// Returns an error if neither `options` nor `gen_options_source` is provided.
     pub fn new(
         options: Option<Vec<String>>,
-        gen_options_command: Option<String>,
-        gen_options_split_by: Option<String>,
+        gen_options_source: Option<OptionSource>,
+        gen_options_cache_ttl: Option<Duration>,
     ) -> Result<Self, &'static str> {
-        if options.is_none() && gen_options_command.is_none() {
-            return Err("At least one of options and gen_options_command must be provided!");
+        if options.is_none() && gen_options_source.is_none() {
+            return Err("At least one of options and gen_options_source must be provided!");
         }
 
         Ok(Self {
-            options: options.unwrap_or_default(),
-            gen_options_command,
-            gen_options_split_by: gen_options_split_by.unwrap_or_else(|| String::from('\n')),
+            options: options
+                .unwrap_or_default()
+                .into_iter()
+                .map(ChooseOption::new)
+                .collect(),
+            gen_options_source,
+            gen_options_cache_ttl,
         })
     }
 }
@@ -115,18 +285,176 @@ impl Parameter for ChooseParameter {
     fn try_assign_value(
         &self,
         value: impl Into<Self::Value>,
-    ) -> Result<ParameterValue<Self>, &'static str> {
+    ) -> Result<ParameterValue<Self>, String> {
         let into_value = value.into();
 
-        if self.gen_options_command.is_some() {
+        if self.gen_options_source.is_some() {
             // With a generated option we can't restrict the option to pre-defined choices.
             return Ok(ParameterValue(into_value));
         }
 
-        if !self.options.contains(&into_value) {
-            return Err("provided value is not a valid option");
+        if !self.options.iter().any(|option| option.value == into_value) {
+            return Err("provided value is not a valid option".into());
         }
 
         Ok(ParameterValue(into_value))
     }
 }
+
+/// Which kinds of filesystem entries a path parameter accepts.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum PathKind {
+    #[default]
+    File,
+    Directory,
+    Any,
+}
+
+impl PathKind {
+    #[must_use]
+    pub fn accepts_files(self) -> bool {
+        matches!(self, PathKind::File | PathKind::Any)
+    }
+
+    #[must_use]
+    pub fn accepts_directories(self) -> bool {
+        matches!(self, PathKind::Directory | PathKind::Any)
+    }
+}
+
+impl TryFrom<&str> for PathKind {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "file" => Ok(Self::File),
+            "directory" => Ok(Self::Directory),
+            "any" => Ok(Self::Any),
+            otherwise => Err(format!("{otherwise} is not a valid path kind")),
+        }
+    }
+}
+
+/// The absolute path of a file or a directory, chosen by browsing the filesystem.
+#[derive(Debug)]
+pub struct PathParameter {
+    /// Directory the browsing starts from, and which cannot be ascended above.
+    pub root: String,
+    /// Glob pattern which the names of the listed files must match.
+    pub glob: Option<String>,
+    pub kind: PathKind,
+    /// Whether the entries whose names start with a dot are listed.
+    pub hidden: bool,
+}
+
+impl Default for PathParameter {
+    fn default() -> Self {
+        Self {
+            root: "~".into(),
+            glob: None,
+            kind: PathKind::default(),
+            hidden: false,
+        }
+    }
+}
+
+impl Parameter for PathParameter {
+    type Value = String;
+
+    fn try_assign_value(
+        &self,
+        value: impl Into<Self::Value>,
+    ) -> Result<ParameterValue<Self>, String> {
+        let into_value = value.into();
+
+        if !Path::new(&into_value).is_absolute() {
+            return Err("Must be an absolute path".into());
+        }
+
+        Ok(ParameterValue(into_value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_value_is_checked_against_length_bounds() {
+        let parameter = TextParameter {
+            min_length: Some(2),
+            max_length: Some(3),
+            ..TextParameter::default()
+        };
+
+        assert!(parameter.try_assign_value("a").is_err());
+        assert!(parameter.try_assign_value("åäö").is_ok());
+        assert!(parameter.try_assign_value("abcd").is_err());
+    }
+
+    #[test]
+    fn empty_text_value_skips_the_checks_unless_required() {
+        let optional = TextParameter {
+            min_length: Some(2),
+            pattern: Some(Regex::new(r"^\d+$").unwrap()),
+            ..TextParameter::default()
+        };
+        assert!(optional.try_assign_value("").is_ok());
+        assert!(optional.try_assign_value("1").is_err());
+
+        let required = TextParameter {
+            required: true,
+            ..optional
+        };
+        assert_eq!(
+            required.try_assign_value("").unwrap_err(),
+            "A value is required"
+        );
+    }
+
+    #[test]
+    fn text_value_is_checked_against_pattern() {
+        let parameter = TextParameter {
+            pattern: Some(Regex::new(r"^\d+$").unwrap()),
+            ..TextParameter::default()
+        };
+
+        assert!(parameter.try_assign_value("8080").is_ok());
+        assert_eq!(
+            parameter.try_assign_value("port").unwrap_err(),
+            r"Must match the pattern ^\d+$"
+        );
+    }
+
+    #[test]
+    fn secret_value_is_redacted_from_debug_output() {
+        let value = SecretParameter::default()
+            .try_assign_value("hunter2".to_string())
+            .unwrap();
+        let variant = ParameterValueVariant::Secret(value);
+
+        assert!(!format!("{variant:?}").contains("hunter2"));
+        assert_eq!(variant.to_substitution_string(), "hunter2");
+    }
+
+    #[test]
+    fn value_is_checked_against_parameter_type() {
+        let character = ParameterDefinitionVariant::Character(CharacterParameter);
+        assert!(character.check_value("a").is_ok());
+        assert!(character.check_value("ab").is_err());
+        assert!(character.check_value("").is_err());
+
+        let choose = ChooseParameter::new(Some(vec!["alpha".into()]), None, None).unwrap();
+        let choose = ParameterDefinitionVariant::Choose(choose);
+        assert!(choose.check_value("alpha").is_ok());
+        assert!(choose.check_value("beta").is_err());
+    }
+
+    #[test]
+    fn path_value_must_be_absolute() {
+        let parameter = PathParameter::default();
+        assert!(parameter.try_assign_value("/home/user/notes.md").is_ok());
+        assert!(parameter.try_assign_value("notes.md").is_err());
+        assert!(parameter.try_assign_value("~/notes.md").is_err());
+    }
+}
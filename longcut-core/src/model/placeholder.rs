@@ -0,0 +1,64 @@
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Matches the innermost `{...}` pairs, capturing their contents.
+static PLACEHOLDER_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\{([^{}]*)}").unwrap());
+
+/// A piece of template text: literal text, or the contents of a `{...}` placeholder.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Segment<'a> {
+    Text(&'a str),
+    Placeholder(&'a str),
+}
+
+/// Splits the text into the placeholders and the literal text around them. Braces enclosing other
+/// braces do not form a placeholder, only the innermost pair does.
+///
+/// # Panics
+///
+/// Panics if the regex pattern for placeholders fails to compile.
+#[must_use]
+pub fn split_placeholders(text: &str) -> Vec<Segment<'_>> {
+    let mut segments = Vec::new();
+    let mut last_match_end: usize = 0;
+    for capture in PLACEHOLDER_PATTERN.captures_iter(text) {
+        let full_match = capture.get(0).unwrap();
+
+        // Capturing the text between each placeholder.
+        let slice = &text[last_match_end..full_match.start()];
+        if !slice.is_empty() {
+            segments.push(Segment::Text(slice));
+        }
+
+        segments.push(Segment::Placeholder(capture.get(1).unwrap().as_str()));
+        last_match_end = full_match.end();
+    }
+
+    // The remainder of the text is added as the final text segment.
+    let slice = &text[last_match_end..];
+    if !slice.is_empty() {
+        segments.push(Segment::Text(slice));
+    }
+
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_is_split_around_placeholders() {
+        assert_eq!(
+            split_placeholders("ls {0} -l {Name}"),
+            vec![
+                Segment::Text("ls "),
+                Segment::Placeholder("0"),
+                Segment::Text(" -l "),
+                Segment::Placeholder("Name"),
+            ]
+        );
+        assert_eq!(split_placeholders("{}"), vec![Segment::Placeholder("")]);
+        assert_eq!(split_placeholders("ls"), vec![Segment::Text("ls")]);
+    }
+}
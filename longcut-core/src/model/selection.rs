@@ -0,0 +1,20 @@
+/// Selections holding text copied by the user.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Selection {
+    /// Text copied explicitly, such as with Ctrl+C.
+    Clipboard,
+    /// Text most recently selected with the mouse.
+    Primary,
+}
+
+impl TryFrom<&str> for Selection {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "clipboard" => Ok(Self::Clipboard),
+            "primary" => Ok(Self::Primary),
+            otherwise => Err(format!("{otherwise} is not a valid selection")),
+        }
+    }
+}
@@ -2,6 +2,13 @@ use crate::model::key::{Key, Symbol};
 use std::collections::BTreeMap;
 use std::ops::Deref;
 
+/// Characters that mnemonic assignment falls back to once the characters of a name run out.
+const FALLBACK_CHARACTERS: &str = "1234567890,.?/!@#$%&";
+
+/// Number of values which [`ShortcutMap::auto_assign_mnemonics`] is guaranteed to find a mnemonic
+/// for, regardless of their names.
+pub const GUARANTEED_MNEMONIC_COUNT: usize = FALLBACK_CHARACTERS.len();
+
 #[derive(Debug, Default)]
 pub struct ShortcutMap<V>(BTreeMap<Key, V>);
 
@@ -24,6 +31,12 @@ impl<V> ShortcutMap<V> {
         }
     }
 
+    /// Returns the value of the shortcut, assigning the shortcut a default value first if it is
+    /// not assigned yet.
+    pub fn get_or_assign(&mut self, shortcut: Key, default: impl FnOnce() -> V) -> &mut V {
+        self.0.entry(shortcut).or_insert_with(default)
+    }
+
     #[must_use]
     pub fn match_exact(&self, shortcut: &Key) -> Option<&V> {
         self.0.get(shortcut)
@@ -32,10 +45,11 @@ impl<V> ShortcutMap<V> {
     /// Returns the value matching the shortcut definition or a modifier-less definition if one exists.
     #[must_use]
     pub fn match_fuzzy(&self, shortcut: &Key) -> Option<&V> {
-        match self.match_exact(shortcut) {
-            Some(exact) => Some(exact),
-            None => self.match_exact(&Key::new(shortcut.symbol.clone())),
-        }
+        self.match_exact(shortcut).or_else(|| {
+            let mut modifierless = Key::new(shortcut.symbol.clone());
+            modifierless.set_physical(shortcut.physical);
+            self.match_exact(&modifierless)
+        })
     }
 
     /// Automatically assigns mnemonic shortcuts to all the provided values based on their names.
@@ -45,14 +59,26 @@ impl<V> ShortcutMap<V> {
     /// later characters in the name, with eventual fall back to set numerical and special characters.
     /// If even this fallback assignment fails, the mnemonic will not be assigned at all, and it is
     /// silently dropped.
-    pub fn auto_assign_mnemonics(&mut self, values: Vec<(&str, V)>) {
-        const FALLBACK_CHARACTERS: &str = "1234567890,.?/!@#$%&";
-
-        // Remaining assignments is initialized using the provided values.
-        let mut remaining_assignments: Vec<(String, V)> = values
-            .into_iter()
-            .map(|(name, value)| (format!("{name}{FALLBACK_CHARACTERS}"), value))
-            .collect();
+    ///
+    /// Values whose names have a `preferred` shortcut are assigned first, and keep that shortcut
+    /// unless another preferred value claimed it already.
+    pub fn auto_assign_mnemonics(
+        &mut self,
+        values: Vec<(&str, V)>,
+        preferred: &BTreeMap<String, Key>,
+    ) {
+        // Remaining assignments is initialized using the values without a usable preference.
+        let mut remaining_assignments: Vec<(String, V)> = vec![];
+        for (name, value) in values {
+            let value = match preferred.get(name) {
+                None => value,
+                Some(key) => match self.try_assign(key.clone(), value) {
+                    Ok(()) => continue,
+                    Err((_, value)) => value,
+                },
+            };
+            remaining_assignments.push((format!("{name}{FALLBACK_CHARACTERS}"), value));
+        }
 
         // As long as we have assignments remaining, we run assignment batches over and over. The
         // failed assignments in every batch are collected into a failures vector, from where they
@@ -151,6 +177,21 @@ mod tests {
         assert!(shortcuts.match_fuzzy(&key_with_mods).is_some());
     }
 
+    #[test]
+    fn physical_keys_match_only_physical_keys() {
+        let mut shortcuts = ShortcutMap::new();
+        let key = Key::new("a".try_into().unwrap());
+        let mut physical_key = key.clone();
+        physical_key.set_physical(true);
+        let mut physical_key_with_mods = physical_key.clone();
+        physical_key_with_mods.add_modifier(Modifier::Control);
+
+        shortcuts.try_assign(physical_key.clone(), 1).unwrap();
+        assert!(shortcuts.match_fuzzy(&key).is_none());
+        assert!(shortcuts.match_fuzzy(&physical_key).is_some());
+        assert!(shortcuts.match_fuzzy(&physical_key_with_mods).is_some());
+    }
+
     #[test]
     fn fuzzy_match_only_matches_down_to_modifierless_keys() {
         // This test mostly just documents the current behaviour. In future, forms of "downmatching"
@@ -173,7 +214,7 @@ mod tests {
         let mut shortcuts = ShortcutMap::new();
 
         let options: Vec<(&str, u8)> = vec![("alpha", 0), ("beta", 1)];
-        shortcuts.auto_assign_mnemonics(options);
+        shortcuts.auto_assign_mnemonics(options, &BTreeMap::new());
 
         let key_a = Key::new("a".try_into().unwrap());
         assert_eq!(shortcuts.match_exact(&key_a).unwrap(), &0);
@@ -190,7 +231,7 @@ mod tests {
         let mut shortcuts = ShortcutMap::new();
 
         let options: Vec<(&str, u8)> = vec![("alpha", 0), ("apple", 1)];
-        shortcuts.auto_assign_mnemonics(options);
+        shortcuts.auto_assign_mnemonics(options, &BTreeMap::new());
 
         let key_a = Key::new("a".try_into().unwrap());
         assert_eq!(shortcuts.match_exact(&key_a).unwrap(), &0);
@@ -206,14 +247,14 @@ mod tests {
         {
             let mut shortcuts = ShortcutMap::new();
             let alpha_first: Vec<(&str, u8)> = vec![("alpha", 0), ("apple", 1)];
-            shortcuts.auto_assign_mnemonics(alpha_first);
+            shortcuts.auto_assign_mnemonics(alpha_first, &BTreeMap::new());
             assert_eq!(shortcuts.match_exact(&key_a).unwrap(), &0);
         }
 
         {
             let mut shortcuts = ShortcutMap::new();
             let apple_first: Vec<(&str, u8)> = vec![("apple", 1), ("alpha", 0)];
-            shortcuts.auto_assign_mnemonics(apple_first);
+            shortcuts.auto_assign_mnemonics(apple_first, &BTreeMap::new());
             assert_eq!(shortcuts.match_exact(&key_a).unwrap(), &1);
         }
     }
@@ -225,7 +266,7 @@ mod tests {
         // Here "abracadabra" has would try to fall back to "b" after being in conflict with "alpha".
         // However, because "banana" maps to "b" conflict-free, it should be preferred.
         let options: Vec<(&str, u8)> = vec![("alpha", 0), ("abracadabra", 1), ("banana", 2)];
-        shortcuts.auto_assign_mnemonics(options);
+        shortcuts.auto_assign_mnemonics(options, &BTreeMap::new());
 
         let key_a = Key::new("a".try_into().unwrap());
         assert_eq!(shortcuts.match_exact(&key_a).unwrap(), &0);
@@ -241,7 +282,7 @@ mod tests {
     fn auto_assign_falls_back_to_a_set_of_predefined_characters() {
         let mut shortcuts = ShortcutMap::new();
         let options: Vec<(&str, u8)> = vec![("a", 0), ("a", 1), ("a", 2)];
-        shortcuts.auto_assign_mnemonics(options);
+        shortcuts.auto_assign_mnemonics(options, &BTreeMap::new());
 
         {
             let key = Key::new("a".try_into().unwrap());
@@ -258,4 +299,49 @@ mod tests {
             assert_eq!(shortcuts.match_exact(&key).unwrap(), &2);
         }
     }
+
+    #[test]
+    fn auto_assign_prefers_the_preferred_shortcuts() {
+        let mut shortcuts = ShortcutMap::new();
+        let key_z = Key::new("z".try_into().unwrap());
+        let preferred = BTreeMap::from([("beta".to_string(), key_z.clone())]);
+
+        let options: Vec<(&str, u8)> = vec![("alpha", 0), ("beta", 1)];
+        shortcuts.auto_assign_mnemonics(options, &preferred);
+
+        assert_eq!(shortcuts.match_exact(&key_z).unwrap(), &1);
+        let key_b = Key::new("b".try_into().unwrap());
+        assert!(shortcuts.match_exact(&key_b).is_none());
+    }
+
+    #[test]
+    fn auto_assign_preferred_shortcuts_take_priority_over_input_order() {
+        let mut shortcuts = ShortcutMap::new();
+        let key_a = Key::new("a".try_into().unwrap());
+        let preferred = BTreeMap::from([("apple".to_string(), key_a.clone())]);
+
+        let options: Vec<(&str, u8)> = vec![("alpha", 0), ("apple", 1)];
+        shortcuts.auto_assign_mnemonics(options, &preferred);
+
+        assert_eq!(shortcuts.match_exact(&key_a).unwrap(), &1);
+        let key_l = Key::new("l".try_into().unwrap());
+        assert_eq!(shortcuts.match_exact(&key_l).unwrap(), &0);
+    }
+
+    #[test]
+    fn auto_assign_falls_back_to_regular_assignment_on_preference_conflict() {
+        let mut shortcuts = ShortcutMap::new();
+        let key_a = Key::new("a".try_into().unwrap());
+        let preferred = BTreeMap::from([
+            ("alpha".to_string(), key_a.clone()),
+            ("beta".to_string(), key_a.clone()),
+        ]);
+
+        let options: Vec<(&str, u8)> = vec![("alpha", 0), ("beta", 1)];
+        shortcuts.auto_assign_mnemonics(options, &preferred);
+
+        assert_eq!(shortcuts.match_exact(&key_a).unwrap(), &0);
+        let key_b = Key::new("b".try_into().unwrap());
+        assert_eq!(shortcuts.match_exact(&key_b).unwrap(), &1);
+    }
 }
@@ -0,0 +1,199 @@
+/// A single line of editable text with a cursor.
+///
+/// The cursor sits between characters, and is expressed as the number of characters before it.
+#[derive(Debug, Default)]
+pub struct TextBuffer {
+    text: String,
+    cursor: usize,
+}
+
+impl TextBuffer {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Buffer holding the text, with the cursor at its end.
+    #[must_use]
+    pub fn with_text(text: String) -> Self {
+        let cursor = text.chars().count();
+        Self { text, cursor }
+    }
+
+    #[must_use]
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    #[must_use]
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    pub fn insert(&mut self, c: char) {
+        let offset = self.byte_offset(self.cursor);
+        self.text.insert(offset, c);
+        self.cursor += 1;
+    }
+
+    pub fn insert_str(&mut self, text: &str) {
+        let offset = self.byte_offset(self.cursor);
+        self.text.insert_str(offset, text);
+        self.cursor += text.chars().count();
+    }
+
+    /// Deletes the character before the cursor. Returns false if there was nothing to delete.
+    pub fn delete_backward(&mut self) -> bool {
+        if self.cursor == 0 {
+            return false;
+        }
+        self.cursor -= 1;
+        self.text.remove(self.byte_offset(self.cursor));
+        true
+    }
+
+    /// Deletes the character after the cursor. Returns false if there was nothing to delete.
+    pub fn delete_forward(&mut self) -> bool {
+        if self.cursor == self.text.chars().count() {
+            return false;
+        }
+        self.text.remove(self.byte_offset(self.cursor));
+        true
+    }
+
+    /// Deletes the word before the cursor, along with any whitespace between it and the cursor.
+    pub fn delete_word_backward(&mut self) {
+        let before: Vec<char> = self.text.chars().take(self.cursor).collect();
+        let trailing_whitespace = before
+            .iter()
+            .rev()
+            .take_while(|c| c.is_whitespace())
+            .count();
+        let word = before
+            .iter()
+            .rev()
+            .skip(trailing_whitespace)
+            .take_while(|c| !c.is_whitespace())
+            .count();
+
+        self.delete_before_cursor(trailing_whitespace + word);
+    }
+
+    /// Deletes everything before the cursor.
+    pub fn delete_to_start(&mut self) {
+        self.delete_before_cursor(self.cursor);
+    }
+
+    pub fn clear(&mut self) {
+        self.text.clear();
+        self.cursor = 0;
+    }
+
+    pub fn move_left(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+
+    pub fn move_right(&mut self) {
+        self.cursor = (self.cursor + 1).min(self.text.chars().count());
+    }
+
+    pub fn move_home(&mut self) {
+        self.cursor = 0;
+    }
+
+    pub fn move_end(&mut self) {
+        self.cursor = self.text.chars().count();
+    }
+
+    fn delete_before_cursor(&mut self, count: usize) {
+        let start = self.byte_offset(self.cursor - count);
+        let end = self.byte_offset(self.cursor);
+        self.text.replace_range(start..end, "");
+        self.cursor -= count;
+    }
+
+    /// Byte offset of the character index within the text.
+    fn byte_offset(&self, index: usize) -> usize {
+        self.text
+            .char_indices()
+            .nth(index)
+            .map_or(self.text.len(), |(offset, _)| offset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn characters_are_inserted_at_cursor() {
+        let mut buffer = TextBuffer::with_text("hllo".to_string());
+        buffer.move_home();
+        buffer.move_right();
+        buffer.insert('e');
+        assert_eq!(buffer.text(), "hello");
+        assert_eq!(buffer.cursor(), 2);
+
+        buffer.move_end();
+        buffer.insert_str(" wörld");
+        assert_eq!(buffer.text(), "hello wörld");
+        assert_eq!(buffer.cursor(), 11);
+    }
+
+    #[test]
+    fn cursor_stays_within_text() {
+        let mut buffer = TextBuffer::with_text("ab".to_string());
+        buffer.move_right();
+        assert_eq!(buffer.cursor(), 2);
+
+        buffer.move_left();
+        buffer.move_left();
+        buffer.move_left();
+        assert_eq!(buffer.cursor(), 0);
+    }
+
+    #[test]
+    fn characters_are_deleted_around_cursor() {
+        let mut buffer = TextBuffer::with_text("añb".to_string());
+        buffer.move_left();
+        assert!(buffer.delete_backward());
+        assert_eq!(buffer.text(), "ab");
+        assert!(buffer.delete_forward());
+        assert_eq!(buffer.text(), "a");
+        assert!(!buffer.delete_forward());
+
+        buffer.move_home();
+        assert!(!buffer.delete_backward());
+    }
+
+    #[test]
+    fn word_before_cursor_is_deleted() {
+        let mut buffer = TextBuffer::with_text("git commit  --amend".to_string());
+        buffer.move_left();
+        buffer.move_left();
+        buffer.move_left();
+        buffer.move_left();
+        buffer.move_left();
+        buffer.move_left();
+        buffer.move_left();
+        buffer.delete_word_backward();
+        assert_eq!(buffer.text(), "git --amend");
+        assert_eq!(buffer.cursor(), 4);
+    }
+
+    #[test]
+    fn everything_before_cursor_is_deleted() {
+        let mut buffer = TextBuffer::with_text("hello world".to_string());
+        for _ in 0..5 {
+            buffer.move_left();
+        }
+        buffer.delete_to_start();
+        assert_eq!(buffer.text(), "world");
+        assert_eq!(buffer.cursor(), 0);
+    }
+}
@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+use std::time::Instant;
+
+/// Remembers values until their expiration time.
+///
+/// The current time is passed in by the caller, which keeps the cache itself deterministic.
+#[derive(Debug)]
+pub struct TimedCache<V> {
+    entries: HashMap<String, (Instant, V)>,
+}
+
+impl<V> Default for TimedCache<V> {
+    fn default() -> Self {
+        Self {
+            entries: HashMap::new(),
+        }
+    }
+}
+
+impl<V> TimedCache<V> {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the value stored for the key, unless it has expired by `now`.
+    #[must_use]
+    pub fn get(&self, key: &str, now: Instant) -> Option<&V> {
+        self.entries
+            .get(key)
+            .filter(|(expires_at, _)| now < *expires_at)
+            .map(|(_, value)| value)
+    }
+
+    /// Stores the value for the key, replacing any earlier value. Expired entries are dropped at
+    /// the same time, so that the cache does not grow without bounds.
+    pub fn insert(&mut self, key: String, value: V, now: Instant, expires_at: Instant) {
+        self.entries.retain(|_, (expiry, _)| now < *expiry);
+        self.entries.insert(key, (expires_at, value));
+    }
+
+    /// Drops the value stored for the key, such as when it is known to be outdated.
+    pub fn remove(&mut self, key: &str) {
+        self.entries.remove(key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn values_are_available_until_they_expire() {
+        let now = Instant::now();
+        let mut cache = TimedCache::new();
+        cache.insert("key".to_string(), 1, now, now + Duration::from_secs(10));
+
+        assert_eq!(cache.get("key", now), Some(&1));
+        assert_eq!(cache.get("key", now + Duration::from_secs(9)), Some(&1));
+        assert_eq!(cache.get("key", now + Duration::from_secs(10)), None);
+        assert_eq!(cache.get("other", now), None);
+    }
+
+    #[test]
+    fn inserting_replaces_and_drops_expired_values() {
+        let now = Instant::now();
+        let mut cache = TimedCache::new();
+        cache.insert("old".to_string(), 1, now, now + Duration::from_secs(1));
+        cache.insert("key".to_string(), 1, now, now + Duration::from_secs(1));
+
+        let later = now + Duration::from_secs(2);
+        cache.insert("key".to_string(), 2, later, later + Duration::from_secs(1));
+
+        assert_eq!(cache.get("key", later), Some(&2));
+        assert_eq!(cache.entries.len(), 1);
+    }
+
+    #[test]
+    fn removed_values_are_gone() {
+        let now = Instant::now();
+        let mut cache = TimedCache::new();
+        cache.insert("key".to_string(), 1, now, now + Duration::from_secs(10));
+        cache.remove("key");
+
+        assert_eq!(cache.get("key", now), None);
+    }
+}
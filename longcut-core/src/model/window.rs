@@ -0,0 +1,42 @@
+/// A top-level window of an application.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClientWindow {
+    /// Identifier of the window within the windowing system.
+    pub id: u64,
+    /// Class name of the application owning the window.
+    pub class: String,
+    pub title: String,
+}
+
+/// Which property of a chosen window a parameter is substituted with.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum WindowField {
+    #[default]
+    Id,
+    Class,
+    Title,
+}
+
+impl ClientWindow {
+    #[must_use]
+    pub fn field(&self, field: WindowField) -> String {
+        match field {
+            WindowField::Id => self.id.to_string(),
+            WindowField::Class => self.class.clone(),
+            WindowField::Title => self.title.clone(),
+        }
+    }
+}
+
+impl TryFrom<&str> for WindowField {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "id" => Ok(Self::Id),
+            "class" => Ok(Self::Class),
+            "title" => Ok(Self::Title),
+            otherwise => Err(format!("{otherwise} is not a valid window field")),
+        }
+    }
+}
@@ -2,8 +2,11 @@ use longcut_config::{ConfigError, ConfigModule, Module};
 
 use crate::config::Config;
 use crate::port::WindowManager;
+use crate::port::clipboard::Clipboard;
 use crate::port::executor::Executor;
+use crate::port::filesystem::Filesystem;
 use crate::port::input::Input;
+use crate::port::storage::Storage;
 use crate::port::view::View;
 use crate::service::CoreService;
 
@@ -21,15 +24,31 @@ impl<'a> CoreModule<'a> {
     /// # Errors
     ///
     /// Returns an error if the module configuration cannot be loaded.
+    #[expect(
+        clippy::too_many_arguments,
+        reason = "every port of the core is injected separately"
+    )]
     pub fn new(
         config_module: &'a ConfigModule,
         input: &'a impl Input,
         view: &'a impl View,
         executor: &'a impl Executor,
         window_manager: &'a impl WindowManager,
+        storage: &'a impl Storage,
+        filesystem: &'a impl Filesystem,
+        clipboard: &'a impl Clipboard,
     ) -> Result<Self, ConfigError> {
         let config = config_module.config_for_module::<Self>()?;
-        let longcut_service = CoreService::new(executor, input, view, window_manager, config);
+        let longcut_service = CoreService::new(
+            executor,
+            input,
+            view,
+            window_manager,
+            storage,
+            filesystem,
+            clipboard,
+            config,
+        );
         Ok(Self { longcut_service })
     }
 }
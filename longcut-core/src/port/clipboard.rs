@@ -0,0 +1,8 @@
+use crate::model::selection::Selection;
+
+/// Provides read access to the text copied by the user.
+pub trait Clipboard {
+    /// Returns the text of the selection, or nothing if the selection is empty or does not hold
+    /// text.
+    fn read(&self, selection: Selection) -> Option<String>;
+}
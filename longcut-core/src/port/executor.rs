@@ -1,3 +1,5 @@
+use std::sync::mpsc::Receiver;
+
 #[derive(Debug)]
 pub enum ExecutorError {
     RuntimeError(String),
@@ -16,6 +18,11 @@ pub trait Executor {
     /// Returns an error if the command fails to start or exits with an error.
     fn run_to_completion(&self, command: &str) -> Result<String, ExecutorError>;
 
+    /// Executes the specified command like [`Executor::run_to_completion`], but without blocking
+    /// the caller. The result is delivered through the returned receiver once the execution
+    /// finishes.
+    fn run_to_completion_async(&self, command: &str) -> Receiver<Result<String, ExecutorError>>;
+
     /// Executes the specified command in the background, continuing on as soon as the program
     /// was launched. Does not block, but also does not report on any errors besides the launch
     /// related ones.
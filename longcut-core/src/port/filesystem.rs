@@ -0,0 +1,51 @@
+#[derive(Debug)]
+pub enum FilesystemError {
+    InvalidPattern(String),
+    Io(String),
+}
+
+/// An entry of a directory.
+#[derive(Debug)]
+pub struct DirectoryEntry {
+    /// Absolute path of the entry.
+    pub path: String,
+    /// Whether the entry is a directory, or a link to one.
+    pub is_directory: bool,
+}
+
+/// Provides read access to the files of the user.
+///
+/// Paths starting with `~` refer to the home directory of the user.
+pub trait Filesystem {
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or is not valid UTF-8.
+    fn read_to_string(&self, path: &str) -> Result<String, FilesystemError>;
+
+    /// Lists the paths matching the glob pattern in alphabetical order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the pattern is invalid or the matching directories cannot be read.
+    fn glob(&self, pattern: &str) -> Result<Vec<String>, FilesystemError>;
+
+    /// Absolute form of the path, without `.` and `..` components or a trailing separator. The
+    /// path does not need to exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the path cannot be made absolute.
+    fn absolute_path(&self, path: &str) -> Result<String, FilesystemError>;
+
+    /// Lists the entries of the directory in alphabetical order. Files are listed only if their
+    /// names match the glob pattern, while directories are always listed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the pattern is invalid or the directory cannot be read.
+    fn list_directory(
+        &self,
+        path: &str,
+        pattern: Option<&str>,
+    ) -> Result<Vec<DirectoryEntry>, FilesystemError>;
+}
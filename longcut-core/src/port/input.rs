@@ -1,15 +1,102 @@
 use crate::model::key::Key;
+use std::time::Duration;
+
+/// Input for entering text: a key press, or text which an input method commits at once.
+pub enum TextEvent {
+    Key(Key),
+    Text(String),
+}
+
+/// A key for [`Input::capture_one`] to capture, by its press or by its tap.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum KeyCapture {
+    /// The press of the key is captured, and does not reach the focused window.
+    Press(Key),
+    /// A press of the key which is released within `max_duration` without other keys being
+    /// pressed meanwhile is captured. The presses reach the focused window as usual, so that the
+    /// key keeps working in combinations with the other keys.
+    Tap { key: Key, max_duration: Duration },
+}
+
+#[derive(Debug)]
+pub enum InputError {
+    /// The entire input device could not be grabbed, as another client holds it. Screen lockers
+    /// and fullscreen games, for example, hold the keyboard.
+    GrabFailed,
+    /// The connection to the input device was lost, such as when the display server exited.
+    Disconnected,
+}
 
 pub trait Input {
     /// Only capture input from the specified keys, returning the first Key encountered.
-    fn capture_one(&self, keys: &[Key]) -> Key;
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input is lost.
+    fn capture_one(&self, keys: &[KeyCapture]) -> Result<Key, InputError>;
+
+    /// Like [`Input::capture_one`], but returns `None` if no key is pressed, and no tap begins,
+    /// within `timeout`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input is lost.
+    fn capture_one_timeout(
+        &self,
+        keys: &[KeyCapture],
+        timeout: Duration,
+    ) -> Result<Option<Key>, InputError>;
 
     /// Grab the entire input device and return an iterator that yields one Key per press.
-    /// The grab is held for the iterator's lifetime and released on drop.
-    fn capture_any_iter(&self) -> Box<dyn Iterator<Item = Key> + '_>;
+    /// The grab is held for the iterator's lifetime and released on drop. Presses repeated by
+    /// holding a key down are ignored. The iterator ends if the input is lost.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input device cannot be grabbed.
+    fn capture_any_iter(&self) -> Result<Box<dyn Iterator<Item = Key> + '_>, InputError>;
+
+    /// Like [`Input::capture_any_iter`], but the iterator yields `None` whenever `timeout` passes
+    /// without a key press. This lets the caller react to other events while holding the grab.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input device cannot be grabbed.
+    fn capture_any_timed_iter(
+        &self,
+        timeout: Duration,
+    ) -> Result<Box<dyn Iterator<Item = Option<Key>> + '_>, InputError>;
+
+    /// Like [`Input::capture_any_timed_iter`], but the key presses pass through the input method
+    /// first. Dead keys and compose sequences yield the composed characters, and the text which
+    /// an input method commits is yielded as a whole. Unlike the other captures, holding a key
+    /// down repeats its press, so that a held Backspace keeps erasing.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input device cannot be grabbed.
+    fn capture_text_timed_iter(
+        &self,
+        timeout: Duration,
+    ) -> Result<Box<dyn Iterator<Item = Option<TextEvent>> + '_>, InputError>;
+
+    /// Capture the entire input device to read a single Key. Presses repeated by holding a key
+    /// down are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input device cannot be grabbed, or the input is lost.
+    fn capture_any(&self) -> Result<Key, InputError>;
+
+    /// Takes the keyboard layout active now as the one which physical keys are placed in, from now
+    /// on regardless of the keyboard layout active at the time of a press.
+    fn use_physical_keys(&self);
+
+    /// The Key captured last, as it is placed in the keyboard layout of the physical keys, if it
+    /// has a place there. The Key is marked physical, so that only physical keys match it.
+    fn physical_key(&self) -> Option<Key>;
 
-    /// Capture the entire input device to read a single Key.
-    fn capture_any(&self) -> Key {
-        self.capture_any_iter().next().unwrap()
-    }
+    /// Waits for the Key captured last to be released, returning whether it was released within
+    /// `timeout`.
+    fn await_release(&self, timeout: Duration) -> bool;
 }
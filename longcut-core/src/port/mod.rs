@@ -1,5 +1,8 @@
+pub mod clipboard;
 pub mod executor;
+pub mod filesystem;
 pub mod input;
+pub mod storage;
 pub mod view;
 pub mod window_manager;
 
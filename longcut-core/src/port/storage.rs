@@ -0,0 +1,44 @@
+#[derive(Debug)]
+pub enum StorageError {
+    /// There is no place to persist the state into.
+    Unavailable,
+    Io(String),
+    Serialization(String),
+}
+
+/// Persists small pieces of state across program runs.
+///
+/// State which was never stored loads as empty.
+pub trait Storage {
+    /// Returns the mnemonic characters previously assigned to the option values of a scope.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the stored mnemonics cannot be read.
+    fn load_mnemonics(&self, scope: &str) -> Result<Vec<(char, String)>, StorageError>;
+
+    /// Replaces the mnemonics stored for the scope with the provided ones.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the mnemonics cannot be persisted.
+    fn store_mnemonics(
+        &self,
+        scope: &str,
+        mnemonics: &[(char, String)],
+    ) -> Result<(), StorageError>;
+
+    /// Returns the values previously entered within a scope, from the most recent to the oldest.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the stored history cannot be read.
+    fn load_history(&self, scope: &str) -> Result<Vec<String>, StorageError>;
+
+    /// Replaces the values stored for the scope with the provided ones.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the history cannot be persisted.
+    fn store_history(&self, scope: &str, entries: &[String]) -> Result<(), StorageError>;
+}
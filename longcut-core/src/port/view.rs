@@ -5,9 +5,16 @@ use crate::model::layer::Layer;
 pub enum ViewAction {
     Branch(String),
     Execute(String),
+    /// Begins multi-key shortcuts of the given layers and commands.
+    Sequence(Vec<String>),
     Unbranch,
     Deactivate,
     Retry,
+    Refresh,
+    /// Skips the input of an optional parameter, leaving it to the given default value.
+    Skip(String),
+    /// Chooses the directory being browsed.
+    ChooseDirectory,
 }
 
 pub type ActionShortcuts<'a> = &'a [(&'a Key, ViewAction)];
@@ -23,12 +30,62 @@ pub struct ErrorViewModel<'a> {
 pub struct LayerNavigationViewModel<'a> {
     pub actions: ActionShortcuts<'a>,
     pub layer_stack: LayerStack<'a>,
+    /// Earlier keys of a multi-key shortcut pressed so far.
+    pub pressed_keys: &'a [Key],
+}
+
+pub enum OptionGenerationStatus<'a> {
+    /// All the options are available.
+    Complete,
+    /// More options are still being generated.
+    Loading,
+    /// Generating the options failed. The generation can be retried with the given actions.
+    Failed {
+        error_details: &'a str,
+        actions: ActionShortcuts<'a>,
+    },
 }
 
 pub enum ParameterVariant<'a> {
     CharInput,
-    StringInput { input_value: &'a str },
-    OptionInput { options: &'a [(&'a Key, &'a str)] },
+    StringInput {
+        input_value: &'a str,
+        /// Number of characters before the cursor.
+        cursor: usize,
+        /// Shown in place of an empty input.
+        placeholder: Option<&'a str>,
+        /// Why the entered text was not accepted.
+        error: Option<&'a str>,
+        /// Visible completions of the entered text.
+        completions: &'a [&'a str],
+        /// Position of the completion accepted into the input among the visible completions.
+        selected_completion: Option<usize>,
+    },
+    /// Text input whose characters are not shown.
+    SecretInput {
+        /// Number of characters entered.
+        length: usize,
+        /// Number of characters before the cursor.
+        cursor: usize,
+        /// Whether the configured command is still producing the value.
+        is_loading: bool,
+        /// Why the value could not be produced by the configured command.
+        error: Option<&'a str>,
+    },
+    OptionInput {
+        /// Options on the visible page, paired with their shortcuts.
+        options: &'a [(&'a Key, &'a str)],
+        filter: &'a str,
+        is_filtering: bool,
+        /// Position of the visible page's first option among all the matching options.
+        offset: usize,
+        match_count: usize,
+        generation: OptionGenerationStatus<'a>,
+        /// Actions available besides choosing one of the options.
+        actions: ActionShortcuts<'a>,
+        /// Directory whose entries the options are, when browsing the filesystem.
+        directory: Option<&'a str>,
+    },
 }
 
 pub struct ParameterInputViewModel<'a> {
@@ -36,6 +93,8 @@ pub struct ParameterInputViewModel<'a> {
     pub parameter_name: &'a str,
     pub parameter: ParameterVariant<'a>,
     pub layer_stack: LayerStack<'a>,
+    /// Actions available regardless of the type of the parameter.
+    pub actions: ActionShortcuts<'a>,
 }
 
 pub enum ViewModel<'a> {
@@ -1,4 +1,9 @@
+use crate::model::window::ClientWindow;
+
 pub trait WindowManager {
     /// Returns the name of the currently active window, or None if unavailable.
     fn get_active_window_name(&self) -> Option<String>;
+
+    /// Lists the top-level windows of the applications, or nothing if they are unavailable.
+    fn list_windows(&self) -> Vec<ClientWindow>;
 }
@@ -1,22 +1,43 @@
+use std::cell::RefCell;
+
 use crate::config::Config;
 use crate::logic::{Context, run_inactive_mode};
-use crate::model::key::{Key, Symbol};
-use crate::port::{WindowManager, executor::Executor, input::Input, view::View};
+use crate::model::key::{Key, Modifier, Symbol};
+use crate::model::timed_cache::TimedCache;
+use crate::port::{
+    WindowManager,
+    clipboard::Clipboard,
+    executor::Executor,
+    filesystem::Filesystem,
+    input::{Input, InputError},
+    storage::Storage,
+    view::View,
+};
 
 pub struct CoreService<'a> {
     executor: &'a dyn Executor,
     input: &'a dyn Input,
     view: &'a dyn View,
     window_manager: &'a dyn WindowManager,
+    storage: &'a dyn Storage,
+    filesystem: &'a dyn Filesystem,
+    clipboard: &'a dyn Clipboard,
     config: Config,
 }
 
 impl<'a> CoreService<'a> {
+    #[expect(
+        clippy::too_many_arguments,
+        reason = "every port of the core is injected separately"
+    )]
     pub fn new(
         executor: &'a dyn Executor,
         input: &'a dyn Input,
         view: &'a dyn View,
         window_manager: &'a dyn WindowManager,
+        storage: &'a dyn Storage,
+        filesystem: &'a dyn Filesystem,
+        clipboard: &'a dyn Clipboard,
         config: Config,
     ) -> Self {
         Self {
@@ -24,28 +45,51 @@ impl<'a> CoreService<'a> {
             input,
             view,
             window_manager,
+            storage,
+            filesystem,
+            clipboard,
             config,
         }
     }
 
-    pub fn run_forever(&self) {
+    /// Runs the program until its input is lost, such as when the display server exits. Returns
+    /// the error which ended the run, after which the ports need to be set up anew.
+    #[must_use]
+    pub fn run(&self) -> InputError {
+        self.input.use_physical_keys();
+
+        let keys_refresh = [Key::new(Symbol::F5)];
         let keys_retry = [Key::new(Symbol::Return)];
+        let mut control_return = Key::new(Symbol::Return);
+        control_return.add_modifier(Modifier::Control);
+        let keys_skip = [control_return];
+        let options_cache = RefCell::new(TimedCache::new());
         let context = Context {
             executor: self.executor,
             input: self.input,
             view: self.view,
             window_manager: self.window_manager,
+            storage: self.storage,
+            filesystem: self.filesystem,
+            clipboard: self.clipboard,
+            options_cache: &options_cache,
             keys_activate: &self.config.keys_activate,
             keys_app_activate: &self.config.keys_app_activate,
             keys_back: &self.config.keys_back,
             keys_deactivate: &self.config.keys_deactivate,
+            keys_refresh: &keys_refresh,
             keys_retry: &keys_retry,
+            keys_skip: &keys_skip,
+            options_per_page: self.config.options_per_page,
+            hold_duration: self.config.hold_duration,
             root_layer: &self.config.root_layer,
             app_specific_layers: &self.config.app_specific_layers,
         };
 
         loop {
-            run_inactive_mode(&context);
+            if let Err(error) = run_inactive_mode(&context) {
+                return error;
+            }
         }
     }
 }
@@ -0,0 +1,66 @@
+use longcut_core::port::filesystem::{DirectoryEntry, Filesystem, FilesystemError};
+use longcut_filesystem::{FilesystemError as ServiceError, FilesystemService};
+
+pub struct LocalFilesystem<'a> {
+    filesystem: &'a FilesystemService,
+}
+
+impl<'a> LocalFilesystem<'a> {
+    #[must_use]
+    pub fn new(filesystem: &'a FilesystemService) -> Self {
+        Self { filesystem }
+    }
+}
+
+impl Filesystem for LocalFilesystem<'_> {
+    fn read_to_string(&self, path: &str) -> Result<String, FilesystemError> {
+        self.filesystem
+            .read_to_string(path)
+            .map_err(into_filesystem_error)
+    }
+
+    fn glob(&self, pattern: &str) -> Result<Vec<String>, FilesystemError> {
+        let paths = self
+            .filesystem
+            .glob(pattern)
+            .map_err(into_filesystem_error)?;
+
+        Ok(paths
+            .into_iter()
+            .map(|path| path.to_string_lossy().into_owned())
+            .collect())
+    }
+
+    fn absolute_path(&self, path: &str) -> Result<String, FilesystemError> {
+        self.filesystem
+            .absolute_path(path)
+            .map(|path| path.to_string_lossy().into_owned())
+            .map_err(into_filesystem_error)
+    }
+
+    fn list_directory(
+        &self,
+        path: &str,
+        pattern: Option<&str>,
+    ) -> Result<Vec<DirectoryEntry>, FilesystemError> {
+        let entries = self
+            .filesystem
+            .list_directory(path, pattern)
+            .map_err(into_filesystem_error)?;
+
+        Ok(entries
+            .into_iter()
+            .map(|entry| DirectoryEntry {
+                path: entry.path.to_string_lossy().into_owned(),
+                is_directory: entry.is_directory,
+            })
+            .collect())
+    }
+}
+
+fn into_filesystem_error(error: ServiceError) -> FilesystemError {
+    match error {
+        ServiceError::InvalidPattern(details) => FilesystemError::InvalidPattern(details),
+        ServiceError::Io(details) => FilesystemError::Io(details),
+    }
+}
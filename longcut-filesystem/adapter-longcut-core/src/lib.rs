@@ -0,0 +1,3 @@
+mod filesystem;
+
+pub use filesystem::LocalFilesystem;
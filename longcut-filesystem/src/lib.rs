@@ -0,0 +1,5 @@
+mod module;
+mod service;
+
+pub use module::FilesystemModule;
+pub use service::{DirectoryEntry, FilesystemError, FilesystemService};
@@ -0,0 +1,25 @@
+use crate::service::FilesystemService;
+use longcut_config::Module;
+
+pub struct FilesystemModule {
+    pub filesystem_service: FilesystemService,
+}
+
+impl Module for FilesystemModule {
+    const IDENTIFIER: &'static str = "filesystem";
+
+    type Config = ();
+}
+
+impl FilesystemModule {
+    /// Paths starting with `~` are resolved against the user's home directory, if it is known.
+    #[expect(
+        clippy::new_without_default,
+        reason = "resolves the user's home directory on construction; Default would hide this"
+    )]
+    #[must_use]
+    pub fn new() -> Self {
+        let filesystem_service = FilesystemService::new(dirs::home_dir());
+        Self { filesystem_service }
+    }
+}
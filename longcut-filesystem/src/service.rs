@@ -0,0 +1,219 @@
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// Reads files and lists directory entries of the local filesystem.
+///
+/// Paths starting with `~` are resolved against the home directory. Without a known home directory
+/// such paths are used as is.
+pub struct FilesystemService {
+    home: Option<PathBuf>,
+}
+
+/// An entry of a directory.
+#[derive(Debug, PartialEq, Eq)]
+pub struct DirectoryEntry {
+    /// Absolute path of the entry.
+    pub path: PathBuf,
+    /// Whether the entry is a directory, or a link to one.
+    pub is_directory: bool,
+}
+
+#[derive(Debug)]
+pub enum FilesystemError {
+    InvalidPattern(String),
+    Io(String),
+}
+
+impl FilesystemService {
+    #[must_use]
+    pub fn new(home: Option<PathBuf>) -> Self {
+        Self { home }
+    }
+
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or is not valid UTF-8.
+    pub fn read_to_string(&self, path: &str) -> Result<String, FilesystemError> {
+        let path = self.expand_home(path);
+        fs::read_to_string(&path)
+            .map_err(|e| FilesystemError::Io(format!("{}: {e}", path.display())))
+    }
+
+    /// Lists the paths matching the glob pattern in alphabetical order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the pattern is invalid or the matching directories cannot be read.
+    pub fn glob(&self, pattern: &str) -> Result<Vec<PathBuf>, FilesystemError> {
+        let pattern = self.expand_home(pattern);
+        let pattern = pattern.to_string_lossy();
+
+        glob::glob(&pattern)
+            .map_err(|e| FilesystemError::InvalidPattern(e.to_string()))?
+            .map(|entry| entry.map_err(|e| FilesystemError::Io(e.to_string())))
+            .collect()
+    }
+
+    /// Absolute form of the path, without `.` and `..` components or a trailing separator. The
+    /// path does not need to exist, so the `..` components are resolved lexically.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the current directory is needed and cannot be resolved.
+    pub fn absolute_path(&self, path: &str) -> Result<PathBuf, FilesystemError> {
+        let path = self.expand_home(path);
+        let absolute = std::path::absolute(&path)
+            .map_err(|e| FilesystemError::Io(format!("{}: {e}", path.display())))?;
+
+        let mut normalized = PathBuf::new();
+        for component in absolute.components() {
+            match component {
+                Component::CurDir => {}
+                Component::ParentDir => {
+                    normalized.pop();
+                }
+                component => normalized.push(component),
+            }
+        }
+        Ok(normalized)
+    }
+
+    /// Lists the entries of the directory in alphabetical order. Files are listed only if their
+    /// names match the glob pattern, while directories are always listed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the pattern is invalid or the directory cannot be read.
+    pub fn list_directory(
+        &self,
+        path: &str,
+        pattern: Option<&str>,
+    ) -> Result<Vec<DirectoryEntry>, FilesystemError> {
+        let pattern = pattern
+            .map(glob::Pattern::new)
+            .transpose()
+            .map_err(|e| FilesystemError::InvalidPattern(e.to_string()))?;
+        let path = self.absolute_path(path)?;
+        let io_error = |e: std::io::Error| FilesystemError::Io(format!("{}: {e}", path.display()));
+
+        let mut entries = Vec::new();
+        for entry in fs::read_dir(&path).map_err(io_error)? {
+            let entry = entry.map_err(io_error)?;
+            let is_directory = entry.path().is_dir();
+            let is_match = pattern
+                .as_ref()
+                .is_none_or(|pattern| pattern.matches(&entry.file_name().to_string_lossy()));
+
+            if is_directory || is_match {
+                entries.push(DirectoryEntry {
+                    path: entry.path(),
+                    is_directory,
+                });
+            }
+        }
+
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(entries)
+    }
+
+    fn expand_home(&self, path: &str) -> PathBuf {
+        match (&self.home, path.strip_prefix('~')) {
+            (Some(home), Some("")) => home.clone(),
+            (Some(home), Some(rest)) if rest.starts_with('/') => {
+                home.join(Path::new(rest.trim_start_matches('/')))
+            }
+            _ => PathBuf::from(path),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn home_directory_is_expanded() {
+        let temporary = tempdir().unwrap();
+        let directory = temporary.path();
+        fs::write(directory.join("hosts"), "alpha\nbeta\n").unwrap();
+
+        let service = FilesystemService::new(Some(directory.to_path_buf()));
+        assert_eq!(service.read_to_string("~/hosts").unwrap(), "alpha\nbeta\n");
+        assert!(service.read_to_string("~other/hosts").is_err());
+    }
+
+    #[test]
+    fn glob_lists_matching_paths_in_order() {
+        let temporary = tempdir().unwrap();
+        let directory = temporary.path();
+        for name in ["b.txt", "a.txt", "c.md"] {
+            fs::write(directory.join(name), "").unwrap();
+        }
+
+        let service = FilesystemService::new(None);
+        let pattern = format!("{}/*.txt", directory.display());
+        let paths = service.glob(&pattern).unwrap();
+        assert_eq!(
+            paths,
+            vec![directory.join("a.txt"), directory.join("b.txt")]
+        );
+    }
+
+    #[test]
+    fn directory_lists_matching_files_and_all_directories() {
+        let temporary = tempdir().unwrap();
+        let directory = temporary.path();
+        fs::create_dir(directory.join("docs")).unwrap();
+        for name in ["b.txt", "a.txt", "c.md"] {
+            fs::write(directory.join(name), "").unwrap();
+        }
+
+        let service = FilesystemService::new(Some(directory.to_path_buf()));
+        let entries = service.list_directory("~", Some("*.txt")).unwrap();
+        assert_eq!(
+            entries,
+            vec![
+                DirectoryEntry {
+                    path: directory.join("a.txt"),
+                    is_directory: false,
+                },
+                DirectoryEntry {
+                    path: directory.join("b.txt"),
+                    is_directory: false,
+                },
+                DirectoryEntry {
+                    path: directory.join("docs"),
+                    is_directory: true,
+                },
+            ]
+        );
+        assert_eq!(service.list_directory("~", None).unwrap().len(), 4);
+    }
+
+    #[test]
+    fn relative_path_is_made_absolute() {
+        let service = FilesystemService::new(None);
+        let path = service.absolute_path("projects").unwrap();
+        assert!(path.is_absolute());
+        assert!(path.ends_with("projects"));
+    }
+
+    #[test]
+    fn absolute_path_is_normalized() {
+        let service = FilesystemService::new(Some(PathBuf::from("/home/user")));
+        let normalized = |path| service.absolute_path(path).unwrap().into_os_string();
+        assert_eq!(normalized("~/projects/"), "/home/user/projects");
+        assert_eq!(normalized("/srv/./data/../logs/"), "/srv/logs");
+        assert_eq!(normalized("/.."), "/");
+    }
+
+    #[test]
+    fn invalid_glob_pattern_is_an_error() {
+        let service = FilesystemService::new(None);
+        assert!(matches!(
+            service.glob("[unclosed"),
+            Err(FilesystemError::InvalidPattern(_))
+        ));
+    }
+}
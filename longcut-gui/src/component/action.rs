@@ -42,9 +42,16 @@ impl Action {
         let (name, kind) = match action {
             ViewAction::Branch(layer) => (layer.clone(), ActionKind::Branch),
             ViewAction::Execute(command) => (command.clone(), ActionKind::Execute),
+            ViewAction::Sequence(names) => (format!("{}…", names.join(", ")), ActionKind::Branch),
             ViewAction::Unbranch => ("Unbranch".to_string(), ActionKind::System),
             ViewAction::Deactivate => ("Deactivate".to_string(), ActionKind::System),
             ViewAction::Retry => ("Retry".to_string(), ActionKind::System),
+            ViewAction::Refresh => ("Refresh".to_string(), ActionKind::System),
+            ViewAction::Skip(default) if default.is_empty() => {
+                ("Skip".to_string(), ActionKind::System)
+            }
+            ViewAction::Skip(default) => (format!("Use {default}"), ActionKind::System),
+            ViewAction::ChooseDirectory => ("Choose directory".to_string(), ActionKind::System),
         };
 
         Self {
@@ -43,6 +43,18 @@ impl Shortcut {
             modifiers += "u-";
         }
 
+        if key.modifiers.contains(&Modifier::Hyper) {
+            modifiers += "h-";
+        }
+
+        if key.modifiers.contains(&Modifier::AltGr) {
+            modifiers += "g-";
+        }
+
+        if key.modifiers.contains(&Modifier::Mod3) {
+            modifiers += "3-";
+        }
+
         let symbol = match &key.symbol {
             Symbol::Character(c) => c.to_string(),
             otherwise => format!("{otherwise:?}").to_lowercase(),
@@ -1,18 +1,24 @@
 use crate::component::action::Action;
 use crate::component::layer_stack::LayerStack;
 use crate::component::root::Root;
+use crate::component::shortcut::Shortcut;
 use crate::model::theme::Theme;
 use itertools::Itertools;
 use longcut_core::port::view::LayerNavigationViewModel;
 use longcut_graphics_lib::component::Component;
 use longcut_graphics_lib::component::column::Column;
+use longcut_graphics_lib::component::row::Row;
 use longcut_graphics_lib::component::table::Table;
+use longcut_graphics_lib::component::text::Text;
 use longcut_graphics_lib::model::unit::Unit;
+use longcut_graphics_lib::property::Property;
 
 #[derive(Debug)]
 pub struct LayerNavigationScreen {
     pub stack: Vec<String>,
     pub actions: Vec<Action>,
+    /// Earlier keys of a multi-key shortcut pressed so far.
+    pub pressed_keys: Vec<Shortcut>,
 }
 
 impl LayerNavigationScreen {
@@ -25,8 +31,25 @@ impl LayerNavigationScreen {
             actions = actions.add_child(action.assemble(theme));
         }
 
+        // The pressed keys are shown after the layer stack, waiting for the rest of the shortcut.
+        let mut header = Row::<Box<dyn Component>>::new().add_child(Box::new(layer_stack));
+        if !self.pressed_keys.is_empty() {
+            let mut pressed_keys = Row::<Box<dyn Component>>::new();
+            for key in &self.pressed_keys {
+                pressed_keys = pressed_keys.add_child(Box::new(key.assemble()));
+            }
+            pressed_keys = pressed_keys.add_child(Box::new(Text::new("…".to_string())));
+
+            let placeholder_color = theme.placeholder_color.clone();
+            header = header.add_child(Box::new(
+                pressed_keys
+                    .gap_size(Unit::Em(0.5))
+                    .foreground(placeholder_color),
+            ));
+        }
+
         let column = Column::<Box<dyn Component>>::new()
-            .add_child(Box::new(layer_stack))
+            .add_child(Box::new(header.gap_size(Unit::Em(1.0))))
             .add_child(Box::new(actions))
             .gap_size(Unit::Em(1.0));
 
@@ -55,7 +78,12 @@ impl From<LayerNavigationViewModel<'_>> for LayerNavigationScreen {
             .map(|(key, action)| Action::new(key, action))
             .sorted()
             .collect();
+        let pressed_keys = data.pressed_keys.iter().map(Shortcut::new).collect();
 
-        Self { stack, actions }
+        Self {
+            stack,
+            actions,
+            pressed_keys,
+        }
     }
 }
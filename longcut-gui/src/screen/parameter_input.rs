@@ -2,7 +2,9 @@ use crate::component::action::Action;
 use crate::component::layer_stack::LayerStack;
 use crate::component::root::Root;
 use crate::model::theme::Theme;
-use longcut_core::port::view::{ParameterInputViewModel, ParameterVariant, ViewAction};
+use longcut_core::port::view::{
+    OptionGenerationStatus, ParameterInputViewModel, ParameterVariant, ViewAction,
+};
 use longcut_graphics_lib::component::Component;
 use longcut_graphics_lib::component::column::Column;
 use longcut_graphics_lib::component::row::Row;
@@ -16,13 +18,53 @@ pub struct ParameterInputScreen {
     pub parameter_name: String,
     pub stack: Vec<String>,
     variant: Variant,
+    actions: Vec<Action>,
 }
 
+/// Drawn between the characters of the text input at the cursor position.
+const CURSOR: char = '\u{258f}';
+
+/// Drawn in place of each character of a secret.
+const SECRET_BULLET: char = '\u{2022}';
+
 #[derive(Debug)]
 enum Variant {
     Character,
-    String { current_input: String },
-    Choose { options: Vec<Action> },
+    String(TextInput),
+    Choose(Choose),
+}
+
+#[derive(Debug)]
+struct TextInput {
+    current_input: String,
+    /// Number of characters before the cursor.
+    cursor: usize,
+    placeholder: Option<String>,
+    error: Option<String>,
+    completions: Vec<String>,
+    selected_completion: Option<usize>,
+}
+
+#[derive(Debug)]
+struct Choose {
+    /// Directory being browsed, when the options are its entries.
+    directory: Option<String>,
+    options: Vec<Action>,
+    filter: String,
+    is_filtering: bool,
+    indicator: Option<String>,
+    generation: Generation,
+    actions: Vec<Action>,
+}
+
+#[derive(Debug)]
+enum Generation {
+    Complete,
+    Loading,
+    Failed {
+        error_details: String,
+        actions: Vec<Action>,
+    },
 }
 
 impl ParameterInputScreen {
@@ -44,46 +86,23 @@ impl ParameterInputScreen {
                         .gap_size(Unit::Em(1.0)),
                 )
             }
-            Variant::String { current_input } => {
-                let prompt = Text::new(format!("{}:", self.parameter_name));
-
-                let text: Box<dyn Component> = if current_input.is_empty() {
-                    let placeholder_text = Text::new("Text".to_string());
-                    let placeholder_color = theme.placeholder_color.clone();
-                    Box::new(placeholder_text.foreground(placeholder_color))
-                } else {
-                    let input_text = Text::new(current_input.clone());
-                    Box::new(input_text)
-                };
-
-                Box::new(
-                    Row::<Box<dyn Component>>::new()
-                        .add_child(Box::new(prompt))
-                        .add_child(text)
-                        .gap_size(Unit::Em(1.0)),
-                )
-            }
-            Variant::Choose { options } => {
-                let prompt = Text::new(format!("{}:", self.parameter_name));
+            Variant::String(text_input) => text_input.assemble(&self.parameter_name, theme),
+            Variant::Choose(choose) => choose.assemble(&self.parameter_name, theme),
+        };
 
-                let mut options_table = Table::new(400);
-                for option in options {
-                    options_table = options_table.add_child(option.assemble(theme));
-                }
+        let mut column = Column::<Box<dyn Component>>::new()
+            .add_child(Box::new(layer_stack))
+            .add_child(content);
 
-                Box::new(
-                    Column::<Box<dyn Component>>::new()
-                        .add_child(Box::new(prompt))
-                        .add_child(Box::new(options_table))
-                        .gap_size(Unit::Em(1.0)),
-                )
+        if !self.actions.is_empty() {
+            let mut actions_table = Table::new(400);
+            for action in &self.actions {
+                actions_table = actions_table.add_child(action.assemble(theme));
             }
-        };
+            column = column.add_child(Box::new(actions_table));
+        }
 
-        let column = Column::<Box<dyn Component>>::new()
-            .add_child(Box::new(layer_stack))
-            .add_child(content)
-            .gap_size(Unit::Em(1.0));
+        let column = column.gap_size(Unit::Em(1.0));
 
         let root = Root::new(
             theme.background_color.clone(),
@@ -97,6 +116,156 @@ impl ParameterInputScreen {
     }
 }
 
+impl TextInput {
+    fn assemble(&self, parameter_name: &str, theme: &Theme) -> Box<dyn Component> {
+        let prompt = Text::new(format!("{parameter_name}:"));
+
+        let text: Box<dyn Component> = if self.current_input.is_empty() {
+            let placeholder = self.placeholder.as_deref().unwrap_or("Text");
+            let placeholder_text = Text::new(placeholder.to_string());
+            let placeholder_color = theme.placeholder_color.clone();
+            Box::new(
+                Row::<Box<dyn Component>>::new()
+                    .add_child(Box::new(Text::new(CURSOR.to_string())))
+                    .add_child(Box::new(placeholder_text.foreground(placeholder_color))),
+            )
+        } else {
+            // The cursor is drawn within the text, as the whitespace around it would otherwise
+            // not be measured.
+            let mut input = self.current_input.clone();
+            let offset = input
+                .char_indices()
+                .nth(self.cursor)
+                .map_or(input.len(), |(offset, _)| offset);
+            input.insert(offset, CURSOR);
+            Box::new(Text::new(input))
+        };
+
+        let input_row = Row::<Box<dyn Component>>::new()
+            .add_child(Box::new(prompt))
+            .add_child(text)
+            .gap_size(Unit::Em(1.0));
+
+        let mut column = Column::<Box<dyn Component>>::new().add_child(Box::new(input_row));
+
+        if let Some(error) = &self.error {
+            let error_color = theme.error_border_color.clone();
+            column = column.add_child(Box::new(Text::new(error.clone()).foreground(error_color)));
+        }
+
+        // The completion accepted into the input stands out from the other completions.
+        for (index, completion) in self.completions.iter().enumerate() {
+            let color = if self.selected_completion == Some(index) {
+                theme.foreground_color.clone()
+            } else {
+                theme.placeholder_color.clone()
+            };
+            column = column.add_child(Box::new(Text::new(completion.clone()).foreground(color)));
+        }
+
+        Box::new(column.gap_size(Unit::Em(0.5)))
+    }
+}
+
+impl Choose {
+    fn assemble(&self, parameter_name: &str, theme: &Theme) -> Box<dyn Component> {
+        let mut header = Row::<Box<dyn Component>>::new()
+            .add_child(Box::new(Text::new(format!("{parameter_name}:"))));
+
+        if let Some(directory) = &self.directory {
+            header = header.add_child(Box::new(Text::new(directory.clone())));
+        }
+
+        // The filter is shown in the placeholder color unless it is being edited.
+        if self.is_filtering || !self.filter.is_empty() {
+            let filter_text = Text::new(format!("/{}", self.filter));
+            if self.is_filtering {
+                header = header.add_child(Box::new(filter_text));
+            } else {
+                let placeholder_color = theme.placeholder_color.clone();
+                header = header.add_child(Box::new(filter_text.foreground(placeholder_color)));
+            }
+        }
+
+        if let Some(indicator) = &self.indicator {
+            let indicator_text = Text::new(indicator.clone());
+            let placeholder_color = theme.placeholder_color.clone();
+            header = header.add_child(Box::new(indicator_text.foreground(placeholder_color)));
+        }
+
+        if let Generation::Loading = &self.generation {
+            let loading_text = Text::new("Loading…".to_string());
+            let placeholder_color = theme.placeholder_color.clone();
+            header = header.add_child(Box::new(loading_text.foreground(placeholder_color)));
+        }
+
+        let mut column =
+            Column::<Box<dyn Component>>::new().add_child(Box::new(header.gap_size(Unit::Em(1.0))));
+
+        if let Generation::Failed {
+            error_details,
+            actions,
+        } = &self.generation
+        {
+            column = column.add_child(assemble_generation_failure(theme, error_details, actions));
+        }
+
+        let options_content: Option<Box<dyn Component>> = if !self.options.is_empty() {
+            let mut options_table = Table::new(400);
+            for option in &self.options {
+                options_table = options_table.add_child(option.assemble(theme));
+            }
+            Some(Box::new(options_table))
+        } else if let Generation::Loading = &self.generation {
+            // Nothing to show until the options arrive.
+            None
+        } else {
+            let placeholder_text = Text::new("No matching options".to_string());
+            let placeholder_color = theme.placeholder_color.clone();
+            Some(Box::new(placeholder_text.foreground(placeholder_color)))
+        };
+
+        if let Some(options_content) = options_content {
+            column = column.add_child(options_content);
+        }
+
+        if !self.actions.is_empty() {
+            let mut actions_table = Table::new(400);
+            for action in &self.actions {
+                actions_table = actions_table.add_child(action.assemble(theme));
+            }
+            column = column.add_child(Box::new(actions_table));
+        }
+
+        Box::new(column.gap_size(Unit::Em(1.0)))
+    }
+}
+
+/// A failed generation is reported inline, as the pre-configured options remain usable regardless.
+fn assemble_generation_failure(
+    theme: &Theme,
+    error_details: &str,
+    actions: &[Action],
+) -> Box<dyn Component> {
+    let mut error_lines: Column<Text> = Column::new();
+    for error_detail in error_details.lines() {
+        error_lines = error_lines.add_child(Text::new(error_detail.to_string()));
+    }
+    let error_color = theme.error_border_color.clone();
+
+    let mut actions_table = Table::new(400);
+    for action in actions {
+        actions_table = actions_table.add_child(action.assemble(theme));
+    }
+
+    Box::new(
+        Column::<Box<dyn Component>>::new()
+            .add_child(Box::new(error_lines.foreground(error_color)))
+            .add_child(Box::new(actions_table))
+            .gap_size(Unit::Em(1.0)),
+    )
+}
+
 impl From<ParameterInputViewModel<'_>> for ParameterInputScreen {
     fn from(data: ParameterInputViewModel) -> Self {
         let mut stack: Vec<String> = data.layer_stack.iter().map(|i| i.name.clone()).collect();
@@ -105,22 +274,101 @@ impl From<ParameterInputViewModel<'_>> for ParameterInputScreen {
 
         let variant: Variant = match &data.parameter {
             ParameterVariant::CharInput => Variant::Character,
-            ParameterVariant::StringInput { input_value } => Variant::String {
+            ParameterVariant::StringInput {
+                input_value,
+                cursor,
+                placeholder,
+                error,
+                completions,
+                selected_completion,
+            } => Variant::String(TextInput {
                 current_input: input_value.to_string(),
-            },
-            ParameterVariant::OptionInput { options } => {
-                let actions = options
+                cursor: *cursor,
+                placeholder: placeholder.map(str::to_string),
+                error: error.map(str::to_string),
+                completions: completions.iter().map(ToString::to_string).collect(),
+                selected_completion: *selected_completion,
+            }),
+            // Secrets are shown as one bullet per character.
+            ParameterVariant::SecretInput {
+                length,
+                cursor,
+                is_loading,
+                error,
+            } => Variant::String(TextInput {
+                current_input: SECRET_BULLET.to_string().repeat(*length),
+                cursor: *cursor,
+                placeholder: Some(if *is_loading { "Loading…" } else { "Secret" }.to_string()),
+                error: error.map(str::to_string),
+                completions: Vec::new(),
+                selected_completion: None,
+            }),
+            ParameterVariant::OptionInput {
+                options,
+                filter,
+                is_filtering,
+                offset,
+                match_count,
+                generation,
+                actions,
+                directory,
+            } => {
+                let options_actions = options
                     .iter()
                     .map(|(key, action)| Action::new(key, &ViewAction::Branch(action.to_string())))
                     .collect();
-                Variant::Choose { options: actions }
+
+                // The "n of m" indicator is only necessary when some of the options are hidden.
+                let is_partial = options.len() < *match_count || !filter.is_empty();
+                let indicator = (is_partial && !options.is_empty()).then(|| {
+                    let first = offset + 1;
+                    let last = offset + options.len();
+                    format!("{first}-{last} of {match_count}")
+                });
+
+                let generation = match generation {
+                    OptionGenerationStatus::Complete => Generation::Complete,
+                    OptionGenerationStatus::Loading => Generation::Loading,
+                    OptionGenerationStatus::Failed {
+                        error_details,
+                        actions,
+                    } => Generation::Failed {
+                        error_details: error_details.to_string(),
+                        actions: actions
+                            .iter()
+                            .map(|(key, action)| Action::new(key, action))
+                            .collect(),
+                    },
+                };
+
+                let actions = actions
+                    .iter()
+                    .map(|(key, action)| Action::new(key, action))
+                    .collect();
+
+                Variant::Choose(Choose {
+                    directory: directory.map(ToString::to_string),
+                    options: options_actions,
+                    filter: filter.to_string(),
+                    is_filtering: *is_filtering,
+                    indicator,
+                    generation,
+                    actions,
+                })
             }
         };
 
+        let actions = data
+            .actions
+            .iter()
+            .map(|(key, action)| Action::new(key, action))
+            .collect();
+
         Self {
             parameter_name: data.parameter_name.to_string(),
             stack,
             variant,
+            actions,
         }
     }
 }
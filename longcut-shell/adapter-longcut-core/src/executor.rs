@@ -1,5 +1,7 @@
 use longcut_core::port::executor::{Executor, ExecutorError};
 use longcut_shell::{RunError, ShellService};
+use std::sync::mpsc::{Receiver, channel};
+use std::thread;
 
 pub struct ShellExecutor<'a> {
     shell: &'a ShellService,
@@ -17,6 +19,20 @@ impl Executor for ShellExecutor<'_> {
         self.shell.run_sync(program).map_err(into_executor_error)
     }
 
+    fn run_to_completion_async(&self, program: &str) -> Receiver<Result<String, ExecutorError>> {
+        let (sender, receiver) = channel();
+        let shell = self.shell.clone();
+        let program = program.to_string();
+
+        thread::spawn(move || {
+            let result = shell.run_sync(&program).map_err(into_executor_error);
+            // The receiver is dropped if the result is no longer of interest. That is fine.
+            let _ = sender.send(result);
+        });
+
+        receiver
+    }
+
     fn run_in_background(&self, program: &str) -> Result<(), ExecutorError> {
         self.shell.run_async(program).map_err(into_executor_error)
     }
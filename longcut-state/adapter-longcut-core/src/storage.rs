@@ -0,0 +1,77 @@
+use longcut_core::port::storage::{Storage, StorageError};
+use longcut_state::{StateError, StateService};
+use std::collections::BTreeMap;
+
+/// Name of the state file holding the mnemonics of every scope.
+const MNEMONICS: &str = "mnemonics";
+
+/// Name of the state file holding the input history of every scope.
+const HISTORY: &str = "history";
+
+/// Mnemonic characters keyed by the option values, under each scope.
+type MnemonicState = BTreeMap<String, BTreeMap<String, char>>;
+
+/// Entered values from the most recent to the oldest, under each scope.
+type HistoryState = BTreeMap<String, Vec<String>>;
+
+pub struct StateStorage<'a> {
+    state: &'a StateService,
+}
+
+impl<'a> StateStorage<'a> {
+    #[must_use]
+    pub fn new(state: &'a StateService) -> Self {
+        Self { state }
+    }
+}
+
+impl Storage for StateStorage<'_> {
+    fn load_mnemonics(&self, scope: &str) -> Result<Vec<(char, String)>, StorageError> {
+        let mut state: MnemonicState = self.state.load(MNEMONICS).map_err(into_storage_error)?;
+        Ok(state
+            .remove(scope)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(value, c)| (c, value))
+            .collect())
+    }
+
+    fn store_mnemonics(
+        &self,
+        scope: &str,
+        mnemonics: &[(char, String)],
+    ) -> Result<(), StorageError> {
+        let mut state: MnemonicState = self.state.load(MNEMONICS).map_err(into_storage_error)?;
+        let scope_mnemonics = mnemonics
+            .iter()
+            .map(|(c, value)| (value.clone(), *c))
+            .collect();
+        state.insert(scope.to_string(), scope_mnemonics);
+
+        self.state
+            .store(MNEMONICS, &state)
+            .map_err(into_storage_error)
+    }
+
+    fn load_history(&self, scope: &str) -> Result<Vec<String>, StorageError> {
+        let mut state: HistoryState = self.state.load(HISTORY).map_err(into_storage_error)?;
+        Ok(state.remove(scope).unwrap_or_default())
+    }
+
+    fn store_history(&self, scope: &str, entries: &[String]) -> Result<(), StorageError> {
+        let mut state: HistoryState = self.state.load(HISTORY).map_err(into_storage_error)?;
+        state.insert(scope.to_string(), entries.to_vec());
+
+        self.state
+            .store(HISTORY, &state)
+            .map_err(into_storage_error)
+    }
+}
+
+fn into_storage_error(error: StateError) -> StorageError {
+    match error {
+        StateError::NoDirectory => StorageError::Unavailable,
+        StateError::Io(details) => StorageError::Io(details),
+        StateError::Serialization(details) => StorageError::Serialization(details),
+    }
+}
@@ -0,0 +1,5 @@
+mod module;
+mod service;
+
+pub use module::StateModule;
+pub use service::{StateError, StateService};
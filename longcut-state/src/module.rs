@@ -0,0 +1,27 @@
+use crate::service::StateService;
+use longcut_config::Module;
+
+pub struct StateModule {
+    pub state_service: StateService,
+}
+
+impl Module for StateModule {
+    const IDENTIFIER: &'static str = "state";
+
+    type Config = ();
+}
+
+impl StateModule {
+    /// State is kept under `$XDG_STATE_HOME/longcut`. If the state directory cannot be resolved,
+    /// the state is not persisted at all.
+    #[expect(
+        clippy::new_without_default,
+        reason = "resolves the user's state directory on construction; Default would hide this"
+    )]
+    #[must_use]
+    pub fn new() -> Self {
+        let directory = dirs::state_dir().map(|path| path.join("longcut"));
+        let state_service = StateService::new(directory);
+        Self { state_service }
+    }
+}
@@ -0,0 +1,115 @@
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+/// Persists serializable values into files within a state directory.
+///
+/// Every value is stored under a name, which maps to a single yaml file in the directory. Reading
+/// a value that was never stored results in the default value.
+pub struct StateService {
+    directory: Option<PathBuf>,
+}
+
+#[derive(Debug)]
+pub enum StateError {
+    /// There is no directory to persist the state into.
+    NoDirectory,
+    Io(String),
+    Serialization(String),
+}
+
+impl StateService {
+    #[must_use]
+    pub fn new(directory: Option<PathBuf>) -> Self {
+        Self { directory }
+    }
+
+    /// Without a state directory, nothing has been stored, and the default value is returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the stored value cannot be read or parsed.
+    pub fn load<T: DeserializeOwned + Default>(&self, name: &str) -> Result<T, StateError> {
+        let Some(path) = self.path_for(name) else {
+            return Ok(T::default());
+        };
+
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(T::default()),
+            Err(e) => return Err(StateError::Io(e.to_string())),
+        };
+        serde_norway::from_str(&contents).map_err(|e| StateError::Serialization(e.to_string()))
+    }
+
+    /// # Errors
+    ///
+    /// Returns an error if the value cannot be serialized or written into the state directory.
+    pub fn store<T: Serialize>(&self, name: &str, value: &T) -> Result<(), StateError> {
+        let Some(directory) = &self.directory else {
+            return Err(StateError::NoDirectory);
+        };
+        let path = directory.join(format!("{name}.yaml"));
+
+        let contents =
+            serde_norway::to_string(value).map_err(|e| StateError::Serialization(e.to_string()))?;
+        fs::create_dir_all(directory).map_err(|e| StateError::Io(e.to_string()))?;
+
+        // The value is written into a temporary file first and then moved into place, so that an
+        // interrupted write never leaves a truncated file behind.
+        let temporary_path = directory.join(format!(".{name}.yaml.tmp"));
+        fs::write(&temporary_path, contents).map_err(|e| StateError::Io(e.to_string()))?;
+        fs::rename(&temporary_path, path).map_err(|e| StateError::Io(e.to_string()))
+    }
+
+    fn path_for(&self, name: &str) -> Option<PathBuf> {
+        let directory = self.directory.as_ref()?;
+        Some(directory.join(format!("{name}.yaml")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use tempfile::tempdir;
+
+    #[test]
+    fn stored_value_can_be_loaded() {
+        let directory = tempdir().unwrap();
+        let state = StateService::new(Some(directory.path().to_path_buf()));
+
+        let value = BTreeMap::from([("alpha".to_string(), 'a'), ("beta".to_string(), 'b')]);
+        state.store("example", &value).unwrap();
+
+        let loaded: BTreeMap<String, char> = state.load("example").unwrap();
+        assert_eq!(loaded, value);
+    }
+
+    #[test]
+    fn missing_value_loads_as_default() {
+        let directory = tempdir().unwrap();
+        let state = StateService::new(Some(directory.path().to_path_buf()));
+        let loaded: Vec<String> = state.load("does-not-exist").unwrap();
+        assert!(loaded.is_empty());
+    }
+
+    #[test]
+    fn unparsable_value_is_an_error() {
+        let directory = tempdir().unwrap();
+        let state = StateService::new(Some(directory.path().to_path_buf()));
+        state.store("example", &"not a list").unwrap();
+
+        let result: Result<Vec<String>, _> = state.load("example");
+        assert!(matches!(result, Err(StateError::Serialization(_))));
+    }
+
+    #[test]
+    fn storing_without_a_directory_is_an_error() {
+        let state = StateService::new(None);
+        let result = state.store("example", &vec!["value"]);
+        assert!(matches!(result, Err(StateError::NoDirectory)));
+    }
+}
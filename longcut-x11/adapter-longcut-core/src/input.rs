@@ -1,150 +1,640 @@
 use longcut_core::model::key::{Key, Modifier, Symbol};
-use longcut_core::port::input::Input;
-use longcut_x11::X11Handle;
-use x11::xlib::{ControlMask, Mod1Mask, Mod4Mask, ShiftMask};
+use longcut_core::port::input::{Input, InputError, KeyCapture, TextEvent};
+use longcut_x11::{X11Error, X11Handle, X11KeyPress};
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+use std::ffi::c_uint;
+use std::time::{Duration, Instant};
+use x11::xlib::{ControlMask, KeySym, Mod1Mask, Mod3Mask, Mod4Mask, ShiftMask};
+
+/// X11 names of the symbols whose names differ from their [`Symbol`] names.
+const X11_SYMBOL_NAMES: &[(Symbol, &str)] = &[
+    (Symbol::AltL, "Alt_L"),
+    (Symbol::AltR, "Alt_R"),
+    (Symbol::BrowserBack, "XF86Back"),
+    (Symbol::BrowserForward, "XF86Forward"),
+    (Symbol::BrowserHome, "XF86HomePage"),
+    (Symbol::BrowserRefresh, "XF86Reload"),
+    (Symbol::CapsLock, "Caps_Lock"),
+    (Symbol::Character(' '), "space"),
+    (Symbol::Control, "Control_L"),
+    (Symbol::Keypad0, "KP_0"),
+    (Symbol::Keypad1, "KP_1"),
+    (Symbol::Keypad2, "KP_2"),
+    (Symbol::Keypad3, "KP_3"),
+    (Symbol::Keypad4, "KP_4"),
+    (Symbol::Keypad5, "KP_5"),
+    (Symbol::Keypad6, "KP_6"),
+    (Symbol::Keypad7, "KP_7"),
+    (Symbol::Keypad8, "KP_8"),
+    (Symbol::Keypad9, "KP_9"),
+    (Symbol::KeypadAdd, "KP_Add"),
+    (Symbol::KeypadDecimal, "KP_Decimal"),
+    (Symbol::KeypadDivide, "KP_Divide"),
+    (Symbol::KeypadEnter, "KP_Enter"),
+    (Symbol::KeypadEqual, "KP_Equal"),
+    (Symbol::KeypadMultiply, "KP_Multiply"),
+    (Symbol::KeypadSubtract, "KP_Subtract"),
+    (Symbol::NumLock, "Num_Lock"),
+    (Symbol::PageDown, "Next"),
+    (Symbol::PageUp, "Prior"),
+    (Symbol::PrintScreen, "Print"),
+    (Symbol::ScrollLock, "Scroll_Lock"),
+    (Symbol::ShiftL, "Shift_L"),
+    (Symbol::ShiftR, "Shift_R"),
+    (Symbol::SuperL, "Super_L"),
+    (Symbol::SuperR, "Super_R"),
+];
+
+/// The symbols of the keys by keycode, unshifted and shifted.
+type PhysicalLayout = BTreeMap<u8, [Option<Symbol>; 2]>;
+
+/// A grab of a single key: the keycode and modifier mask to grab, and the key they stand for.
+#[derive(Clone)]
+struct KeyGrab {
+    keycode: u8,
+    modifiers: c_uint,
+    key: Key,
+    /// How long a tap of the key may last, if its taps are captured rather than its presses.
+    max_tap_duration: Option<Duration>,
+}
+
+/// Whether the presses repeated by holding a key down are read. They are skipped unless the
+/// reading opts in, as only text entry has any use for them.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+enum Repeats {
+    #[default]
+    Skipped,
+    Kept,
+}
+
+impl Repeats {
+    fn accepts(self, is_repeat: bool) -> bool {
+        self == Repeats::Kept || !is_repeat
+    }
+}
+
+/// Outcome of waiting for a press of the grabbed keys.
+enum GrabbedPress {
+    Pressed(KeyGrab, X11KeyPress),
+    TimedOut,
+    /// The keyboard mapping changed, so that the keys must be grabbed anew.
+    KeymapChanged,
+}
 
 pub struct X11Input<'a> {
     x11: &'a X11Handle,
+    /// Keycode of the key captured last, whose release can be awaited.
+    last_keycode: Cell<Option<u8>>,
+    /// The key captured last as it is placed in the physical layout, if it has a place there.
+    last_physical_key: RefCell<Option<Key>>,
+    /// Layout which the keys of physical shortcuts are placed in, once taken.
+    physical_layout: RefCell<Option<PhysicalLayout>>,
 }
 
 impl<'a> X11Input<'a> {
     #[must_use]
     pub fn new(x11: &'a X11Handle) -> Self {
-        Self { x11 }
+        Self {
+            x11,
+            last_keycode: Cell::new(None),
+            last_physical_key: RefCell::new(None),
+            physical_layout: RefCell::new(None),
+        }
     }
 
     /// Loops on reading x11 key press events until the first one which is a valid key.
-    fn await_for_input(&self) -> Key {
+    fn await_for_input(&self, repeats: Repeats) -> Result<Key, X11Error> {
+        loop {
+            let event = self.x11.read_next_keypress()?;
+            if !repeats.accepts(event.is_repeat) {
+                continue;
+            }
+            if let Some(key) = self.to_key(&event) {
+                self.record_capture(&event);
+                return Ok(key);
+            }
+        }
+    }
+
+    /// Like [`X11Input::await_for_input`], but returns `None` if no valid key is pressed within
+    /// `timeout`.
+    fn await_for_input_timeout(
+        &self,
+        timeout: Duration,
+        repeats: Repeats,
+    ) -> Result<Option<Key>, X11Error> {
+        let deadline = Instant::now() + timeout;
         loop {
-            let event = self.x11.read_next_keypress();
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let Some(event) = self.x11.read_next_keypress_timeout(remaining)? else {
+                return Ok(None);
+            };
+            if !repeats.accepts(event.is_repeat) {
+                continue;
+            }
+            if let Some(key) = self.to_key(&event) {
+                self.record_capture(&event);
+                return Ok(Some(key));
+            }
+        }
+    }
 
+    /// Like [`X11Input::await_for_input_timeout`], but the key presses pass through the input
+    /// method, and the text it commits is returned as a whole.
+    fn await_for_text_event_timeout(
+        &self,
+        timeout: Duration,
+        repeats: Repeats,
+    ) -> Result<Option<TextEvent>, X11Error> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let Some(event) = self.x11.read_next_text_keypress_timeout(remaining)? else {
+                return Ok(None);
+            };
+            if !repeats.accepts(event.is_repeat) {
+                continue;
+            }
             let grapheme = self.x11.keypress_to_grapheme(&event);
-            let key_name = self.x11.keypress_to_key_name(&event);
-            let parsed_symbol = match (key_name, grapheme) {
-                (None, None) => continue,
-                (Some(k), None) => x11_name_to_symbol(k.as_str()),
-                (None, Some(g)) => x11_name_to_symbol(g.as_str()),
-                (Some(k), Some(g)) => {
-                    let ksym = x11_name_to_symbol(k.as_str());
-                    let gsym = x11_name_to_symbol(g.as_str());
-
-                    if let Ok(ksymbol) = &ksym {
-                        if let Symbol::Character(_) = &ksymbol {
-                            // If the key name maps into a single character representation, a character
-                            // was typed -> return the grapheme instead.
-                            gsym
-                        } else {
-                            // The key name maps into a special character -> return the special char.
-                            ksym
-                        }
-                    } else {
-                        // Key name mapping failed, return the grapheme.
+
+            // Input methods commit text of several characters at once, which no key stands for.
+            if let Some(text) = grapheme.as_ref().filter(|text| text.chars().count() > 1) {
+                return Ok(Some(TextEvent::Text(text.clone())));
+            }
+            if let Some(key) = self.to_key_with_grapheme(&event, grapheme) {
+                self.record_capture(&event);
+                return Ok(Some(TextEvent::Key(key)));
+            }
+        }
+    }
+
+    /// Converts the x11 key press event into a key, if it represents a valid one.
+    fn to_key(&self, event: &X11KeyPress) -> Option<Key> {
+        self.to_key_with_grapheme(event, self.x11.keypress_to_grapheme(event))
+    }
+
+    /// Like [`X11Input::to_key`], but with the grapheme looked up already. The text which an input
+    /// method composes can be looked up only once.
+    fn to_key_with_grapheme(&self, event: &X11KeyPress, grapheme: Option<String>) -> Option<Key> {
+        let key_name = self.x11.keypress_to_key_name(event);
+        let parsed_symbol = match (key_name, grapheme) {
+            (None, None) => return None,
+            (Some(k), None) => x11_name_to_symbol(k.as_str()),
+            (None, Some(g)) => x11_name_to_symbol(g.as_str()),
+            (Some(k), Some(g)) => {
+                let ksym = x11_name_to_symbol(k.as_str());
+                let gsym = x11_name_to_symbol(g.as_str());
+
+                if let Ok(ksymbol) = &ksym {
+                    if let Ok(Symbol::Character(c)) = &gsym
+                        && c.is_control()
+                    {
+                        // Control combinations such as Ctrl+W produce control characters, which
+                        // are reported by the key name instead.
+                        ksym
+                    } else if let Symbol::Character(_) = &ksymbol {
+                        // If the key name maps into a single character representation, a character
+                        // was typed -> return the grapheme instead.
                         gsym
+                    } else {
+                        // The key name maps into a special character -> return the special char.
+                        ksym
                     }
+                } else {
+                    // Key name mapping failed, return the grapheme.
+                    gsym
                 }
-            };
+            }
+        };
 
-            let mut press = if let Ok(symbol) = parsed_symbol {
-                Key::new(symbol)
-            } else {
-                println!("{event:?} was not a valid symbol!");
-                continue;
-            };
+        let mut press = if let Ok(symbol) = parsed_symbol {
+            Key::new(symbol)
+        } else {
+            println!("{event:?} was not a valid symbol!");
+            return None;
+        };
 
-            // Active modifier states are added to the key press.
-            if event.is_mod_active(ShiftMask) {
-                press.add_modifier(Modifier::Shift);
-            }
+        self.add_active_modifiers(&mut press, event);
+        Some(press)
+    }
+
+    /// Remembers the key of the x11 key press event as the key captured last.
+    fn record_capture(&self, event: &X11KeyPress) {
+        self.last_keycode.set(Some(event.keycode));
+        *self.last_physical_key.borrow_mut() = self.to_physical_key(event);
+    }
 
-            if event.is_mod_active(ControlMask) {
-                press.add_modifier(Modifier::Control);
+    /// Converts the x11 key press event into a physical key, resolved by its keycode in the
+    /// physical layout, if it has a place there.
+    fn to_physical_key(&self, event: &X11KeyPress) -> Option<Key> {
+        let physical_layout = self.physical_layout.borrow();
+        let levels = physical_layout.as_ref()?.get(&event.keycode)?;
+        let symbol = if event.is_mod_active(ShiftMask) {
+            levels[1].as_ref().or(levels[0].as_ref())
+        } else {
+            levels[0].as_ref()
+        };
+        let mut press = Key::new(symbol?.clone());
+        press.set_physical(true);
+        self.add_active_modifiers(&mut press, event);
+        Some(press)
+    }
+
+    /// Active modifier states are added to the key press.
+    fn add_active_modifiers(&self, press: &mut Key, event: &X11KeyPress) {
+        for (modifier, mask) in self.modifier_masks() {
+            if event.is_mod_active(mask) {
+                press.add_modifier(modifier);
             }
+        }
+    }
+
+    /// Modifiers and the masks they are mapped to. Unlike the fixed masks, Hyper and `AltGr` are
+    /// looked up from the modifier mapping. A modifier sharing the mask of an earlier one, such as
+    /// Hyper mapped along with Super, is left out. So are the lock modifiers such as Num Lock and
+    /// Caps Lock, as they do not change which key is meant.
+    fn modifier_masks(&self) -> Vec<(Modifier, c_uint)> {
+        let masks = [
+            (Modifier::Shift, ShiftMask),
+            (Modifier::Control, ControlMask),
+            (Modifier::Alt, Mod1Mask),
+            (Modifier::Super, Mod4Mask),
+            (Modifier::Hyper, self.x11.modifier_mask("Hyper_L")),
+            (Modifier::AltGr, self.x11.modifier_mask("ISO_Level3_Shift")),
+            (Modifier::Mod3, Mod3Mask),
+        ];
+
+        let mut taken = 0;
+        masks
+            .into_iter()
+            .filter(|(_, mask)| {
+                let is_distinct = *mask != 0 && *mask & taken == 0;
+                taken |= mask;
+                is_distinct
+            })
+            .collect()
+    }
+
+    /// Combined mask of the modifiers, which the grabbed keys are matched against.
+    fn modifier_mask(&self) -> c_uint {
+        self.modifier_masks()
+            .iter()
+            .fold(0, |combined, (_, mask)| combined | mask)
+    }
+
+    /// Returns `None` if any of the modifiers is not mapped to a mask, such as Hyper without a
+    /// Hyper key. Grabbing without the modifier would grab the bare key instead.
+    fn modifiers_to_x11_mask(&self, key: &Key) -> Option<c_uint> {
+        key.modifiers
+            .iter()
+            .map(|modifier| match modifier {
+                Modifier::Shift => ShiftMask,
+                Modifier::Control => ControlMask,
+                Modifier::Alt => Mod1Mask,
+                Modifier::Super => Mod4Mask,
+                Modifier::Hyper => self.x11.modifier_mask("Hyper_L"),
+                Modifier::AltGr => self.x11.modifier_mask("ISO_Level3_Shift"),
+                Modifier::Mod3 => Mod3Mask,
+            })
+            .try_fold(0, |mask, modifier| {
+                (modifier != 0).then_some(mask | modifier)
+            })
+    }
+
+    /// Keys whose symbols have no keycode in the current keyboard layout cannot be grabbed, and
+    /// are left out, as are the keys with a modifier which no key is mapped to. The keycodes of
+    /// the physical keys are those of the physical layout.
+    fn keys_to_x11_grabs(&self, keys: &[KeyCapture]) -> Vec<KeyGrab> {
+        let physical_layout = self.physical_layout.borrow();
+        keys.iter()
+            .filter_map(|capture| {
+                let (key, max_tap_duration) = match capture {
+                    KeyCapture::Press(key) => (key, None),
+                    KeyCapture::Tap { key, max_duration } => (key, Some(*max_duration)),
+                };
+                let physical_keycode = physical_layout
+                    .as_ref()
+                    .filter(|_| key.physical)
+                    .and_then(|layout| physical_keycode(layout, &key.symbol));
+                let keycode = physical_keycode
+                    .or_else(|| self.x11.string_to_keycode(&symbol_to_x11_name(&key.symbol)))?;
+                let Some(modifiers) = self.modifiers_to_x11_mask(key) else {
+                    eprintln!("{key:?} was not grabbed, as its modifiers are not all mapped!");
+                    return None;
+                };
+                Some(KeyGrab {
+                    keycode,
+                    modifiers,
+                    key: key.clone(),
+                    max_tap_duration,
+                })
+            })
+            .collect()
+    }
 
-            if event.is_mod_active(Mod1Mask) {
-                press.add_modifier(Modifier::Alt);
+    /// Loops on reading the presses of the grabbed keys until one matches a grab, until
+    /// `deadline` passes, or until the keyboard mapping changes.
+    fn await_for_grabbed(
+        &self,
+        grabs: &[KeyGrab],
+        deadline: Option<Instant>,
+    ) -> Result<GrabbedPress, X11Error> {
+        loop {
+            let Some(event) = self.x11.read_next_keypress_until_keymap_change(deadline)? else {
+                return Ok(if self.x11.take_keymap_change() {
+                    GrabbedPress::KeymapChanged
+                } else {
+                    GrabbedPress::TimedOut
+                });
+            };
+            if let Some(grab) = self.grabbed_key(grabs, &event) {
+                return Ok(GrabbedPress::Pressed(grab.clone(), event));
             }
+        }
+    }
 
-            if event.is_mod_active(Mod4Mask) {
-                press.add_modifier(Modifier::Super);
+    fn grabbed_key<'g>(&self, grabs: &'g [KeyGrab], event: &X11KeyPress) -> Option<&'g KeyGrab> {
+        let modifier_mask = self.modifier_mask();
+        grabs.iter().find(|grab| {
+            grab.keycode == event.keycode && grab.modifiers == event.modmask & modifier_mask
+        })
+    }
+
+    /// Captures the presses and taps of the keys until one is captured, or until no key has been
+    /// pressed by `deadline`. The keys are grabbed anew whenever the keyboard mapping changes.
+    fn capture_until(
+        &self,
+        keys: &[KeyCapture],
+        deadline: Option<Instant>,
+    ) -> Result<Option<Key>, X11Error> {
+        loop {
+            let grabs = self.keys_to_x11_grabs(keys);
+            for grab in &grabs {
+                if grab.max_tap_duration.is_some() {
+                    self.x11.grab_key_replayable(grab.keycode, grab.modifiers);
+                } else {
+                    self.x11.grab_key(grab.keycode, grab.modifiers);
+                }
             }
+            let capture = loop {
+                // The grabs are gone along with a lost connection, leaving nothing to free.
+                let (grab, event) = match self.await_for_grabbed(&grabs, deadline)? {
+                    GrabbedPress::Pressed(grab, event) => (grab, event),
+                    GrabbedPress::TimedOut => break Some(None),
+                    GrabbedPress::KeymapChanged => break None,
+                };
+                let Some(max_duration) = grab.max_tap_duration else {
+                    break Some(Some(grab.key));
+                };
 
-            return press;
+                // The press reaches the focused window regardless, so that the key keeps working
+                // in combinations with the other keys.
+                if self
+                    .x11
+                    .replay_and_await_lone_release(event.keycode, max_duration)?
+                {
+                    break Some(Some(grab.key));
+                }
+            };
+            self.x11
+                .free_keys(grabs.iter().map(|grab| (grab.keycode, grab.modifiers)));
+            if let Some(key) = capture {
+                return Ok(key);
+            }
         }
     }
+}
 
-    fn keys_to_x11_keycodes(&self, keys: &[Key]) -> Vec<u8> {
-        keys.iter()
-            .map(|key| symbol_to_x11_name(&key.symbol))
-            .filter_map(|sym| self.x11.string_to_keycode(&sym))
-            .collect()
+/// Holds the keyboard grab for its lifetime.
+struct KeyboardGrab<'a> {
+    input: &'a X11Input<'a>,
+}
+
+impl<'a> KeyboardGrab<'a> {
+    fn new(input: &'a X11Input<'a>) -> Result<Self, InputError> {
+        input.x11.grab_keyboard().map_err(to_input_error)?;
+        Ok(Self { input })
+    }
+}
+
+impl Drop for KeyboardGrab<'_> {
+    fn drop(&mut self) {
+        self.input.x11.free_keyboard();
     }
 }
 
+/// Yields key presses while holding the keyboard grab. With a timeout, `None` is yielded whenever
+/// the timeout passes without a key press. Ends once the connection is lost.
 struct KeysIter<'a> {
-    input: &'a X11Input<'a>,
+    grab: KeyboardGrab<'a>,
+    timeout: Option<Duration>,
+    repeats: Repeats,
 }
 
 impl<'a> KeysIter<'a> {
-    fn new(input: &'a X11Input<'a>) -> Self {
-        input.x11.grab_keyboard();
-        Self { input }
+    fn new(input: &'a X11Input<'a>, timeout: Option<Duration>) -> Result<Self, InputError> {
+        Ok(Self {
+            grab: KeyboardGrab::new(input)?,
+            timeout,
+            repeats: Repeats::default(),
+        })
     }
 }
 
 impl Iterator for KeysIter<'_> {
-    type Item = Key;
-    fn next(&mut self) -> Option<Key> {
-        Some(self.input.await_for_input())
+    type Item = Option<Key>;
+    fn next(&mut self) -> Option<Option<Key>> {
+        let input = self.grab.input;
+        match self.timeout {
+            Some(timeout) => input.await_for_input_timeout(timeout, self.repeats).ok(),
+            None => input.await_for_input(self.repeats).ok().map(Some),
+        }
     }
 }
 
-impl Drop for KeysIter<'_> {
-    fn drop(&mut self) {
-        self.input.x11.free_keyboard();
+/// Yields text events while holding the keyboard grab, and `None` whenever the timeout passes
+/// without one. Holding a key down repeats its press, such as to keep erasing with Backspace.
+/// Ends once the connection is lost.
+struct TextEventsIter<'a> {
+    grab: KeyboardGrab<'a>,
+    timeout: Duration,
+}
+
+impl Iterator for TextEventsIter<'_> {
+    type Item = Option<TextEvent>;
+    fn next(&mut self) -> Option<Option<TextEvent>> {
+        self.grab
+            .input
+            .await_for_text_event_timeout(self.timeout, Repeats::Kept)
+            .ok()
     }
 }
 
 impl Input for X11Input<'_> {
-    fn capture_one(&self, keys: &[Key]) -> Key {
-        let keycodes: Vec<u8> = self.keys_to_x11_keycodes(keys);
-        self.x11.grab_keys(keycodes.clone());
-        let key = self.await_for_input();
-        self.x11.free_keys(keycodes);
-        key
+    fn capture_one(&self, keys: &[KeyCapture]) -> Result<Key, InputError> {
+        // Without a deadline, the capture does not time out.
+        loop {
+            let capture = self.capture_until(keys, None);
+            if let Some(key) = capture.map_err(to_input_error)? {
+                return Ok(key);
+            }
+        }
+    }
+
+    fn capture_one_timeout(
+        &self,
+        keys: &[KeyCapture],
+        timeout: Duration,
+    ) -> Result<Option<Key>, InputError> {
+        self.capture_until(keys, Some(Instant::now() + timeout))
+            .map_err(to_input_error)
+    }
+
+    fn capture_text_timed_iter(
+        &self,
+        timeout: Duration,
+    ) -> Result<Box<dyn Iterator<Item = Option<TextEvent>> + '_>, InputError> {
+        Ok(Box::new(TextEventsIter {
+            grab: KeyboardGrab::new(self)?,
+            timeout,
+        }))
+    }
+
+    fn capture_any(&self) -> Result<Key, InputError> {
+        let mut keys = KeysIter::new(self, None)?.flatten();
+        keys.next().ok_or(InputError::Disconnected)
+    }
+
+    fn await_release(&self, timeout: Duration) -> bool {
+        let Some(keycode) = self.last_keycode.get() else {
+            return true;
+        };
+
+        // The keyboard is held meanwhile if possible, so that the repeated presses of the key do
+        // not reach the focused window.
+        let _grab = KeyboardGrab::new(self);
+        self.x11.await_release(keycode, timeout)
     }
 
-    fn capture_any_iter(&self) -> Box<dyn Iterator<Item = Key> + '_> {
-        Box::new(KeysIter::new(self))
+    fn use_physical_keys(&self) {
+        let group = self.x11.active_group();
+        let layout = (u8::MIN..=u8::MAX)
+            .filter_map(|keycode| {
+                let levels = [0, 1].map(|level| {
+                    self.x11
+                        .keycode_to_keysym(keycode, group, level)
+                        .and_then(keysym_to_symbol)
+                });
+                levels
+                    .iter()
+                    .any(Option::is_some)
+                    .then_some((keycode, levels))
+            })
+            .collect();
+        *self.physical_layout.borrow_mut() = Some(layout);
+    }
+
+    fn physical_key(&self) -> Option<Key> {
+        self.last_physical_key.borrow().clone()
+    }
+
+    fn capture_any_iter(&self) -> Result<Box<dyn Iterator<Item = Key> + '_>, InputError> {
+        Ok(Box::new(KeysIter::new(self, None)?.flatten()))
+    }
+
+    fn capture_any_timed_iter(
+        &self,
+        timeout: Duration,
+    ) -> Result<Box<dyn Iterator<Item = Option<Key>> + '_>, InputError> {
+        Ok(Box::new(KeysIter::new(self, Some(timeout))?))
     }
 }
 
 fn symbol_to_x11_name(symbol: &Symbol) -> String {
+    if let Some((_, name)) = X11_SYMBOL_NAMES.iter().find(|(named, _)| named == symbol) {
+        return (*name).to_string();
+    }
+
     match symbol {
-        Symbol::AltL => "Alt_L".to_string(),
-        Symbol::AltR => "Alt_R".to_string(),
-        Symbol::PageDown => "Next".to_string(),
-        Symbol::PageUp => "Prior".to_string(),
-        Symbol::ShiftL => "Shift_L".to_string(),
-        Symbol::ShiftR => "Shift_R".to_string(),
-        Symbol::SuperL => "Super_L".to_string(),
-        Symbol::SuperR => "Super_R".to_string(),
         Symbol::Character(c) => c.to_string(),
+        Symbol::AudioLowerVolume
+        | Symbol::AudioMicMute
+        | Symbol::AudioMute
+        | Symbol::AudioNext
+        | Symbol::AudioPause
+        | Symbol::AudioPlay
+        | Symbol::AudioPrev
+        | Symbol::AudioRaiseVolume
+        | Symbol::AudioStop
+        | Symbol::Calculator
+        | Symbol::Eject
+        | Symbol::Mail
+        | Symbol::MonBrightnessDown
+        | Symbol::MonBrightnessUp
+        | Symbol::Search
+        // The special function keys are named after their X11 names without the prefix.
+        | Symbol::Sleep => format!("XF86{symbol:?}"),
         otherwise => format!("{otherwise:?}"),
     }
 }
 
-fn x11_name_to_symbol(name: &str) -> Result<Symbol, ()> {
-    let symbol = match name {
-        "Alt_L" => Symbol::AltL,
-        "Alt_R" => Symbol::AltR,
-        "Prior" => Symbol::PageUp,
-        "Next" => Symbol::PageDown,
-        "Shift_L" => Symbol::ShiftL,
-        "Shift_R" => Symbol::ShiftR,
-        "Super_L" => Symbol::SuperL,
-        "Super_R" => Symbol::SuperR,
-        otherwise => Symbol::try_from(otherwise).map_err(|_| ())?,
+/// Keycode of the key which the symbol is on in the physical layout, if any.
+fn physical_keycode(layout: &PhysicalLayout, symbol: &Symbol) -> Option<u8> {
+    layout
+        .iter()
+        .find(|(_, levels)| levels.iter().any(|level| level.as_ref() == Some(symbol)))
+        .map(|(keycode, _)| *keycode)
+}
+
+fn keysym_to_symbol(keysym: KeySym) -> Option<Symbol> {
+    // The key symbols of the printable Latin-1 characters equal their code points, while those of
+    // the other Unicode characters are offset by 0x0100_0000.
+    let code_point = match keysym {
+        0x20..=0x7e | 0xa0..=0xff => Some(keysym),
+        0x0100_0000..=0x0110_ffff => Some(keysym - 0x0100_0000),
+        _ => None,
     };
+    if let Some(character) = code_point
+        .and_then(|code_point| u32::try_from(code_point).ok())
+        .and_then(char::from_u32)
+    {
+        return Some(Symbol::Character(character));
+    }
+
+    x11_name_to_symbol(&X11Handle::keysym_to_name(keysym)?).ok()
+}
+
+fn x11_name_to_symbol(name: &str) -> Result<Symbol, ()> {
+    if let Some((symbol, _)) = X11_SYMBOL_NAMES.iter().find(|(_, named)| *named == name) {
+        return Ok(symbol.clone());
+    }
+
+    Symbol::try_from(name).map_err(|_| ())
+}
+
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "used with map_err, which hands over the error by value"
+)]
+fn to_input_error(error: X11Error) -> InputError {
+    match error {
+        X11Error::KeyboardGrabFailed => InputError::GrabFailed,
+        X11Error::DisplayUnavailable(_)
+        | X11Error::InputContextUnavailable
+        | X11Error::InputExtensionUnavailable
+        | X11Error::ConnectionLost => InputError::Disconnected,
+    }
+}
 
-    Ok(symbol)
+#[cfg(test)]
+mod tests {
+    use super::Repeats;
+
+    #[test]
+    fn repeated_presses_are_skipped_by_default() {
+        assert!(Repeats::default().accepts(false));
+        assert!(!Repeats::default().accepts(true));
+        assert!(Repeats::Kept.accepts(true));
+    }
 }
@@ -1,3 +1,4 @@
+use longcut_core::model::window::ClientWindow;
 use longcut_core::port::WindowManager;
 use longcut_x11::X11Handle;
 
@@ -21,4 +22,20 @@ impl WindowManager for X11WindowManager<'_> {
             .get_window_class(window)
             .map(|(_instance, class)| class)
     }
+
+    fn list_windows(&self) -> Vec<ClientWindow> {
+        self.handle
+            .get_client_list()
+            .into_iter()
+            .map(|window| ClientWindow {
+                id: window,
+                class: self
+                    .handle
+                    .get_window_class(window)
+                    .map(|(_instance, class)| class)
+                    .unwrap_or_default(),
+                title: self.handle.get_window_title(window).unwrap_or_default(),
+            })
+            .collect()
+    }
 }
@@ -1,19 +1,110 @@
-use std::ffi::{CStr, CString, c_char, c_int, c_uint, c_ulong, c_void};
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+use std::env;
+use std::ffi::{CStr, CString, c_char, c_int, c_long, c_uint, c_ulong, c_void};
 use std::ops::BitAnd;
 use std::ptr;
+use std::thread;
+use std::time::{Duration, Instant};
+use x11::xinput2::{
+    XI_LASTEVENT, XI_RawKeyPress, XI_RawKeyRelease, XIAllMasterDevices, XIEventMask, XIKeyRepeat,
+    XIQueryVersion, XIRawEvent, XISelectEvents, XISetMask,
+};
 use x11::xlib::{
-    Atom, CurrentTime, Display, GrabModeAsync, KeyPress, NoSymbol, XA_STRING, XA_WINDOW,
-    XCloseDisplay, XCreateIC, XDefaultRootWindow, XEvent, XFree, XGetWindowProperty, XGrabKey,
-    XGrabKeyboard, XIC, XID, XIM, XIMPreeditNothing, XIMStatusNothing, XInternAtom, XKeyEvent,
-    XKeysymToKeycode, XKeysymToString, XNClientWindow, XNInputStyle, XNextEvent, XOpenDisplay,
-    XOpenIM, XStringToKeysym, XSync, XUngrabKey, XUngrabKeyboard, XkbKeycodeToKeysym,
-    Xutf8LookupString,
+    AnyPropertyType, Atom, CurrentTime, Display, GenericEvent, GrabModeAsync, GrabModeSync,
+    GrabSuccess, KeyPress, KeyRelease, KeySym, LockMask, MappingNotify, NoSymbol, ReplayKeyboard,
+    XA_STRING, XA_WINDOW, XAllowEvents, XBufferOverflow, XCloseDisplay, XConnectionNumber,
+    XCreateIC, XDefaultRootWindow, XEvent, XFilterEvent, XFree, XFreeEventData, XFreeModifiermap,
+    XGetEventData, XGetModifierMapping, XGetWindowProperty, XGrabKey, XGrabKeyboard, XIC, XID, XIM,
+    XIMPreeditNothing, XIMStatusNothing, XInternAtom, XKeyEvent, XKeysymToKeycode, XKeysymToString,
+    XLookupBoth, XLookupChars, XNClientWindow, XNFocusWindow, XNInputStyle, XNextEvent,
+    XOpenDisplay, XOpenIM, XPending, XPutBackEvent, XQueryExtension, XQueryKeymap,
+    XRefreshKeyboardMapping, XSetICFocus, XSetIOErrorHandler, XSetLocaleModifiers, XStringToKeysym,
+    XSupportsLocale, XSync, XUngrabKey, XUngrabKeyboard, XkbAnyEvent, XkbGetState,
+    XkbKeycodeToKeysym, XkbMapNotify, XkbMapNotifyEvent, XkbMapNotifyMask, XkbNewKeyboardNotify,
+    XkbNewKeyboardNotifyMask, XkbQueryExtension, XkbRefreshKeyboardMapping, XkbSelectEvents,
+    XkbSetDetectableAutoRepeat, XkbStateRec, Xutf8LookupString,
 };
 
+/// Signature of `XSetIOErrorExitHandler`, which sets the function called after an I/O error of
+/// the display in place of exiting the process. Available since libX11 1.7.
+type SetIOErrorExitHandler = unsafe extern "C" fn(
+    display: *mut Display,
+    handler: Option<unsafe extern "C" fn(*mut Display, *mut c_void)>,
+    user_data: *mut c_void,
+);
+
+/// Leaves the I/O error to [`mark_connection_lost`], after which the lost connection is told by
+/// [`X11Error::ConnectionLost`].
+unsafe extern "C" fn ignore_io_error(_display: *mut Display) -> c_int {
+    0
+}
+
+/// Marks the connection of the display lost instead of exiting the process, so that the X server
+/// can be reconnected to. `user_data` points to the `connection_lost` flag of the handle.
+unsafe extern "C" fn mark_connection_lost(_display: *mut Display, user_data: *mut c_void) {
+    let connection_lost = unsafe { &*user_data.cast::<Cell<bool>>() };
+    connection_lost.set(true);
+}
+
+/// Device specifier of the core keyboard in the XKB requests.
+const XKB_USE_CORE_KEYBOARD: c_uint = 0x0100;
+
+/// Delays between the attempts to grab the keyboard, growing until the grab is given up on.
+const GRAB_RETRY_DELAYS: [Duration; 6] = [
+    Duration::from_millis(10),
+    Duration::from_millis(25),
+    Duration::from_millis(50),
+    Duration::from_millis(100),
+    Duration::from_millis(200),
+    Duration::from_millis(400),
+];
+
+/// How often pending events are checked for while waiting for a key press with a timeout.
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
 pub struct X11Handle {
     display: *mut Display,
     input_context: XIC,
     root_window: XID,
+    /// Keys pressed down as far as the read key events tell, for recognizing repeated presses.
+    held_keycodes: RefCell<Vec<u8>>,
+    /// Event type of the XKB events, or `None` if the server lacks the XKB extension.
+    xkb_event_type: Option<c_int>,
+    /// Major opcode of the input extension, which its events are marked with.
+    xinput_opcode: c_int,
+    /// Whether the keyboard mapping has changed since [`X11Handle::take_keymap_change`].
+    keymap_changed: Cell<bool>,
+    /// Modifier masks looked up by key symbol, forgotten whenever the keyboard mapping changes.
+    modifier_masks: RefCell<BTreeMap<String, c_uint>>,
+    /// Set once the connection to the X server breaks. Boxed to keep its address stable, as the
+    /// I/O error handler of the display refers to it.
+    connection_lost: Box<Cell<bool>>,
+}
+
+#[derive(Debug)]
+pub enum X11Error {
+    /// The display could not be opened. Holds the value of `DISPLAY`, if set.
+    DisplayUnavailable(Option<String>),
+    /// The input method of the locale could not be opened.
+    InputContextUnavailable,
+    /// The X server lacks version 2.1 of the input extension, which tells of the key events
+    /// delivered to other clients.
+    InputExtensionUnavailable,
+    /// Another client held the keyboard grab for longer than it was retried for.
+    KeyboardGrabFailed,
+    /// The connection to the X server broke, such as when the server exited.
+    ConnectionLost,
+}
+
+/// How the key presses are read by [`X11Handle::read_keypress_until`].
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum KeypressReading {
+    Plain,
+    /// The key events pass through the input method first.
+    ThroughInputMethod,
+    /// The reading stops once the keyboard mapping changes.
+    UntilKeymapChange,
 }
 
 #[derive(Debug)]
@@ -21,76 +112,315 @@ pub struct X11KeyPress {
     event: XKeyEvent,
     pub modmask: u32,
     pub keycode: u8,
+    /// Whether the press was repeated by holding the key down, rather than pressed anew.
+    pub is_repeat: bool,
 }
 
 impl X11KeyPress {
+    #[must_use]
     pub fn is_mod_active(&self, mask: c_uint) -> bool {
         mask == self.modmask.bitand(mask)
     }
 }
 
+/// A key event as told by the raw events of the input extension.
+struct RawKeyEvent {
+    keycode: u8,
+    is_release: bool,
+    /// Whether the press was repeated by holding the key down.
+    is_repeat: bool,
+}
+
+/// Data of a window property as returned by Xlib, which is freed when dropped.
+struct Property {
+    data: *mut u8,
+    format: c_int,
+    item_count: usize,
+    /// Bytes of the property left out of the data.
+    bytes_after: c_ulong,
+}
+
+impl Property {
+    /// Items of a property with 8-bit format.
+    fn bytes(&self) -> Option<&[u8]> {
+        (self.format == 8).then(|| self.items::<u8>())
+    }
+
+    /// Items of a property with 32-bit format, which Xlib hands over as longs.
+    fn longs(&self) -> Option<&[c_ulong]> {
+        (self.format == 32).then(|| self.items::<c_ulong>())
+    }
+
+    /// Items of the type matching the format, as Xlib allocates the data as an array of them.
+    fn items<T>(&self) -> &[T] {
+        if self.data.is_null() {
+            return &[];
+        }
+        unsafe { std::slice::from_raw_parts(self.data.cast::<T>(), self.item_count) }
+    }
+}
+
+impl Drop for Property {
+    fn drop(&mut self) {
+        if !self.data.is_null() {
+            unsafe { XFree(self.data.cast::<c_void>()) };
+        }
+    }
+}
+
 impl X11Handle {
-    /// # Panics
+    /// Connects to the X server of the `DISPLAY` environment variable.
     ///
-    /// Panics if the X11 display cannot be opened or input context cannot be loaded.
-    #[expect(
-        clippy::new_without_default,
-        reason = "opens the X11 display and input context on construction; Default would hide this"
-    )]
-    #[must_use]
-    pub fn new() -> Self {
+    /// # Errors
+    ///
+    /// Returns an error if the display cannot be opened or the input context cannot be loaded.
+    pub fn new() -> Result<Self, X11Error> {
         let display = unsafe { XOpenDisplay(ptr::null()) };
+        if display.is_null() {
+            return Err(X11Error::DisplayUnavailable(env::var("DISPLAY").ok()));
+        }
+
+        let connection_lost = Box::new(Cell::new(false));
+        Self::watch_connection(display, &connection_lost);
+
         let root_window = unsafe { XDefaultRootWindow(display) };
-        let input_context = Self::load_input_context(display, root_window)
-            .expect("Failed to load X11 input context");
+        let Some(input_context) = Self::load_input_context(display, root_window) else {
+            unsafe { XCloseDisplay(display) };
+            return Err(X11Error::InputContextUnavailable);
+        };
+        let Some(xinput_opcode) = Self::query_input_extension(display) else {
+            unsafe { XCloseDisplay(display) };
+            return Err(X11Error::InputExtensionUnavailable);
+        };
 
-        Self {
+        // Holding a key down repeats only its press, without the release which would otherwise
+        // come before each repeated press. This tells the repeated presses apart from new ones.
+        unsafe { XkbSetDetectableAutoRepeat(display, c_int::from(true), ptr::null_mut()) };
+
+        let xkb_event_type = Self::select_keymap_events(display);
+
+        Ok(Self {
             display,
             input_context,
             root_window,
+            held_keycodes: RefCell::new(vec![]),
+            xkb_event_type,
+            xinput_opcode,
+            keymap_changed: Cell::new(false),
+            modifier_masks: RefCell::new(BTreeMap::new()),
+            connection_lost,
+        })
+    }
+
+    /// Whether the connection to the X server has been lost, after which the handle stays
+    /// unusable. A new handle is needed to reconnect.
+    #[must_use]
+    pub fn is_connection_lost(&self) -> bool {
+        self.connection_lost.get()
+    }
+
+    /// Grabs the key with the modifiers, regardless of whether the lock modifiers such as Num Lock
+    /// and Caps Lock are active.
+    pub fn grab_key(&self, keycode: u8, modifiers: c_uint) {
+        self.grab_key_in_mode(keycode, modifiers, GrabModeAsync);
+    }
+
+    pub fn grab_keys(&self, keys: impl IntoIterator<Item = (u8, c_uint)>) {
+        for (keycode, modifiers) in keys {
+            self.grab_key(keycode, modifiers);
+        }
+    }
+
+    /// Like [`X11Handle::grab_key`], but the keyboard is frozen once the key is pressed, so that
+    /// the press can be replayed to the focused window with [`X11Handle::replay_keyboard`].
+    pub fn grab_key_replayable(&self, keycode: u8, modifiers: c_uint) {
+        self.grab_key_in_mode(keycode, modifiers, GrabModeSync);
+    }
+
+    pub fn grab_keys_replayable(&self, keys: impl IntoIterator<Item = (u8, c_uint)>) {
+        for (keycode, modifiers) in keys {
+            self.grab_key_replayable(keycode, modifiers);
         }
     }
 
-    pub fn grab_key(&self, keycode: u8) {
+    /// Releases the grab activated by the press of a replayable key, delivering the press to the
+    /// focused window as if the key had not been grabbed.
+    pub fn replay_keyboard(&self) {
         unsafe {
-            XGrabKey(
-                self.display,
-                c_int::from(keycode),
-                0,
-                self.root_window,
-                c_int::from(true),
-                GrabModeAsync,
-                GrabModeAsync,
-            )
-        };
+            XAllowEvents(self.display, ReplayKeyboard, CurrentTime);
+        }
+    }
+
+    /// Returns the keycodes of the keys which are currently held down.
+    #[must_use]
+    #[expect(
+        clippy::cast_sign_loss,
+        reason = "the keymap is a 256-bit vector stored in signed bytes by the FFI layer"
+    )]
+    pub fn pressed_keycodes(&self) -> Vec<u8> {
+        let mut keymap: [c_char; 32] = [0; 32];
+        unsafe {
+            XQueryKeymap(self.display, keymap.as_mut_ptr());
+        }
+
+        (0..=u8::MAX)
+            .filter(|keycode| {
+                let byte = keymap[usize::from(keycode / 8)] as u8;
+                byte & (1 << (keycode % 8)) != 0
+            })
+            .collect()
+    }
+
+    /// Like [`X11Handle::replay_keyboard`], but then waits for the replayed key to be released,
+    /// returning whether it was released within `timeout` without other keys being pressed or
+    /// released meanwhile. The keys held down from before, such as the modifiers of the key, count
+    /// only if released.
+    ///
+    /// The release is delivered to the focused window along with the press, so the raw key events
+    /// of the input extension are read meanwhile, as they tell of the key events of every window.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the connection is lost meanwhile.
+    pub fn replay_and_await_lone_release(
+        &self,
+        keycode: u8,
+        timeout: Duration,
+    ) -> Result<bool, X11Error> {
+        let deadline = Instant::now() + timeout;
+
+        // The raw events are selected before the replay, so that even an instant release is told.
+        self.select_raw_key_events(true);
+        self.replay_keyboard();
+        let is_lone_release = self.read_lone_release_until(keycode, deadline);
+        self.select_raw_key_events(false);
+        is_lone_release
+    }
+
+    /// Waits for the key to be released, returning whether it was released within `timeout`.
+    #[must_use]
+    pub fn await_release(&self, keycode: u8, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        while self.pressed_keycodes().contains(&keycode) {
+            if Instant::now() >= deadline {
+                return false;
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+        true
     }
 
-    pub fn grab_keys(&self, keys: impl IntoIterator<Item = u8>) {
-        for key in keys {
-            self.grab_key(key);
+    /// Releases the grabs of [`X11Handle::grab_key`] and [`X11Handle::grab_key_replayable`].
+    pub fn free_key(&self, keycode: u8, modifiers: c_uint) {
+        for lock_modifiers in self.lock_modifier_combinations() {
+            unsafe {
+                XUngrabKey(
+                    self.display,
+                    c_int::from(keycode),
+                    modifiers | lock_modifiers,
+                    self.root_window,
+                )
+            };
         }
     }
 
-    pub fn free_key(&self, keycode: u8) {
-        unsafe { XUngrabKey(self.display, c_int::from(keycode), 0, self.root_window) };
+    pub fn free_keys(&self, keys: impl IntoIterator<Item = (u8, c_uint)>) {
+        for (keycode, modifiers) in keys {
+            self.free_key(keycode, modifiers);
+        }
     }
 
-    pub fn free_keys(&self, keys: impl IntoIterator<Item = u8>) {
-        for key in keys {
-            self.free_key(key);
+    /// Grabs the key once for every combination of the lock modifiers, as the grab only matches
+    /// presses with the exact modifiers it was made with.
+    fn grab_key_in_mode(&self, keycode: u8, modifiers: c_uint, keyboard_mode: c_int) {
+        for lock_modifiers in self.lock_modifier_combinations() {
+            unsafe {
+                XGrabKey(
+                    self.display,
+                    c_int::from(keycode),
+                    modifiers | lock_modifiers,
+                    self.root_window,
+                    c_int::from(true),
+                    GrabModeAsync,
+                    keyboard_mode,
+                )
+            };
         }
     }
 
-    pub fn grab_keyboard(&self) {
+    /// Every combination of the Caps Lock and Num Lock modifiers, including neither of them.
+    fn lock_modifier_combinations(&self) -> Vec<c_uint> {
+        lock_modifier_combinations(self.modifier_mask("Num_Lock"))
+    }
+
+    /// Returns the modifier mask which the key of the key symbol, such as `Num_Lock` or
+    /// `ISO_Level3_Shift`, is mapped to, or 0 if it is not mapped to any. The masks are looked up
+    /// once per keyboard mapping.
+    #[must_use]
+    pub fn modifier_mask(&self, symbol: &str) -> c_uint {
+        if let Some(mask) = self.modifier_masks.borrow().get(symbol) {
+            return *mask;
+        }
+
+        let mask = self.look_up_modifier_mask(symbol);
+        self.modifier_masks
+            .borrow_mut()
+            .insert(symbol.to_string(), mask);
+        mask
+    }
+
+    fn look_up_modifier_mask(&self, symbol: &str) -> c_uint {
+        let Some(modifier_key) = self.string_to_keycode(symbol) else {
+            return 0;
+        };
+
         unsafe {
-            XGrabKeyboard(
-                self.display,
-                self.root_window,
-                c_int::from(true),
-                GrabModeAsync,
-                GrabModeAsync,
-                CurrentTime,
-            );
+            let modifier_map = XGetModifierMapping(self.display);
+            if modifier_map.is_null() {
+                return 0;
+            }
+
+            // The map lists the keycodes of the eight modifiers, max_keypermod keycodes each.
+            let keys_per_modifier = usize::try_from((*modifier_map).max_keypermod).unwrap_or(0);
+            let keycodes =
+                std::slice::from_raw_parts((*modifier_map).modifiermap, 8 * keys_per_modifier);
+            let mask = keycodes
+                .iter()
+                .position(|keycode| *keycode == modifier_key)
+                .map_or(0, |index| 1 << (index / keys_per_modifier));
+
+            XFreeModifiermap(modifier_map);
+            mask
+        }
+    }
+
+    /// Grabs the entire keyboard. Another client may hold the grab for a moment, such as during a
+    /// drag, so the grab is retried with growing delays before giving up.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the grab cannot be taken, or the connection has been lost.
+    pub fn grab_keyboard(&self) -> Result<(), X11Error> {
+        let mut delays = GRAB_RETRY_DELAYS.iter();
+        loop {
+            let status = unsafe {
+                XGrabKeyboard(
+                    self.display,
+                    self.root_window,
+                    c_int::from(true),
+                    GrabModeAsync,
+                    GrabModeAsync,
+                    CurrentTime,
+                )
+            };
+            self.check_connection()?;
+            if status == GrabSuccess {
+                return Ok(());
+            }
+            let Some(delay) = delays.next() else {
+                return Err(X11Error::KeyboardGrabFailed);
+            };
+            thread::sleep(*delay);
         }
     }
 
@@ -105,25 +435,194 @@ impl X11Handle {
 
     /// Blocks on the next `XEvent` of `KeyPress` type to happen, and returns the keycode and mod mask
     /// tuple of the key.
-    #[must_use]
-    #[expect(
-        clippy::cast_possible_truncation,
-        reason = "X11 keycodes are 8-bit values stored in a wider integer by the FFI layer"
-    )]
-    pub fn read_next_keypress(&self) -> X11KeyPress {
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the connection is lost meanwhile.
+    pub fn read_next_keypress(&self) -> Result<X11KeyPress, X11Error> {
         loop {
-            let x_event = self.read_next_event();
-            if x_event.get_type() == KeyPress {
-                let event = XKeyEvent::from(x_event);
-                return X11KeyPress {
-                    event,
-                    modmask: event.state,
-                    keycode: event.keycode as u8,
-                };
+            if let Some(press) = self.read_keypress_until(None, KeypressReading::Plain)? {
+                return Ok(press);
             }
         }
     }
 
+    /// Like [`X11Handle::read_next_keypress`], but gives up and returns `None` if no key is
+    /// pressed within `timeout`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the connection is lost meanwhile.
+    pub fn read_next_keypress_timeout(
+        &self,
+        timeout: Duration,
+    ) -> Result<Option<X11KeyPress>, X11Error> {
+        self.read_keypress_until(Some(Instant::now() + timeout), KeypressReading::Plain)
+    }
+
+    /// Like [`X11Handle::read_next_keypress_timeout`], but the key events pass through the input
+    /// method first. The presses which the input method consumes, such as those of dead keys, are
+    /// left out, while the text it composes or commits is returned as presses of their own.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the connection is lost meanwhile.
+    pub fn read_next_text_keypress_timeout(
+        &self,
+        timeout: Duration,
+    ) -> Result<Option<X11KeyPress>, X11Error> {
+        self.read_keypress_until(
+            Some(Instant::now() + timeout),
+            KeypressReading::ThroughInputMethod,
+        )
+    }
+
+    /// Like [`X11Handle::read_next_keypress`], but returns `None` once `deadline` passes, or once
+    /// the keyboard mapping changes, as told by [`X11Handle::take_keymap_change`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the connection is lost meanwhile.
+    pub fn read_next_keypress_until_keymap_change(
+        &self,
+        deadline: Option<Instant>,
+    ) -> Result<Option<X11KeyPress>, X11Error> {
+        self.read_keypress_until(deadline, KeypressReading::UntilKeymapChange)
+    }
+
+    fn read_keypress_until(
+        &self,
+        deadline: Option<Instant>,
+        reading: KeypressReading,
+    ) -> Result<Option<X11KeyPress>, X11Error> {
+        self.forget_released_keys();
+        loop {
+            if reading == KeypressReading::UntilKeymapChange && self.keymap_changed.get() {
+                return Ok(None);
+            }
+            let Some(mut event) = self.read_event_until(deadline)? else {
+                return Ok(None);
+            };
+            if reading == KeypressReading::ThroughInputMethod
+                && unsafe { XFilterEvent(&raw mut event, 0) } != 0
+            {
+                continue;
+            }
+            if let Some(press) = self.track_key_event(event) {
+                return Ok(Some(press));
+            }
+        }
+    }
+
+    /// Reads the raw key events until the key is released or another key is pressed or released,
+    /// returning whether the key was released first, by `deadline`.
+    fn read_lone_release_until(&self, keycode: u8, deadline: Instant) -> Result<bool, X11Error> {
+        while let Some(mut event) = self.read_event_until(Some(deadline))? {
+            let event_type = event.get_type();
+
+            // The presses of the grabbed keys are left to be read as usual.
+            if event_type == KeyPress || event_type == KeyRelease {
+                unsafe { XPutBackEvent(self.display, &raw mut event) };
+                return Ok(false);
+            }
+            if event_type != GenericEvent {
+                self.track_key_event(event);
+                continue;
+            }
+            match self.read_raw_key_event(&mut event) {
+                Some(raw) if raw.keycode == keycode && raw.is_release => return Ok(true),
+                Some(raw) if raw.keycode != keycode && !raw.is_repeat => return Ok(false),
+                _ => {}
+            }
+        }
+        Ok(false)
+    }
+
+    /// Waits on the connection for the pending events rather than blocking in Xlib, so that a lost
+    /// connection is noticed instead of waited on. Returns `None` once `deadline` passes without
+    /// an event.
+    fn read_event_until(&self, deadline: Option<Instant>) -> Result<Option<XEvent>, X11Error> {
+        loop {
+            if unsafe { XPending(self.display) } > 0 {
+                return Ok(Some(self.read_next_event()));
+            }
+            self.check_connection()?;
+
+            let timeout = match deadline {
+                None => None,
+                Some(deadline) => {
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+                    if remaining.is_zero() {
+                        return Ok(None);
+                    }
+                    Some(remaining)
+                }
+            };
+            self.await_connection(timeout);
+        }
+    }
+
+    /// Blocks until the connection has data to read or breaks, or until `timeout` passes.
+    fn await_connection(&self, timeout: Option<Duration>) {
+        // The timeout is rounded up, so that a deadline less than a millisecond away is waited on.
+        let timeout_ms = timeout.map_or(-1, |timeout| {
+            c_int::try_from(timeout.as_micros().div_ceil(1000)).unwrap_or(c_int::MAX)
+        });
+        let mut connection = libc::pollfd {
+            fd: unsafe { XConnectionNumber(self.display) },
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        unsafe { libc::poll(&raw mut connection, 1, timeout_ms) };
+    }
+
+    /// Selects or deselects the raw key events of the input extension, which tell of the key
+    /// events regardless of the window they are delivered to.
+    fn select_raw_key_events(&self, is_selected: bool) {
+        let mut mask = [0; XI_LASTEVENT as usize / 8 + 1];
+        if is_selected {
+            XISetMask(&mut mask, XI_RawKeyPress);
+            XISetMask(&mut mask, XI_RawKeyRelease);
+        }
+        let mut event_mask = XIEventMask {
+            deviceid: XIAllMasterDevices,
+            mask_len: c_int::try_from(mask.len()).unwrap_or_default(),
+            mask: mask.as_mut_ptr(),
+        };
+        unsafe { XISelectEvents(self.display, self.root_window, &raw mut event_mask, 1) };
+    }
+
+    /// Returns the raw key event of the input extension, if the event is one.
+    fn read_raw_key_event(&self, event: &mut XEvent) -> Option<RawKeyEvent> {
+        let cookie = unsafe { &mut event.generic_event_cookie };
+        if cookie.extension != self.xinput_opcode
+            || unsafe { XGetEventData(self.display, cookie) } == 0
+        {
+            return None;
+        }
+
+        let raw_event = unsafe { &*cookie.data.cast::<XIRawEvent>() };
+        let is_key_event = cookie.evtype == XI_RawKeyPress || cookie.evtype == XI_RawKeyRelease;
+        let key_event = is_key_event
+            .then_some(cookie.evtype == XI_RawKeyRelease)
+            .zip(u8::try_from(raw_event.detail).ok())
+            .map(|(is_release, keycode)| RawKeyEvent {
+                keycode,
+                is_release,
+                is_repeat: raw_event.flags & XIKeyRepeat != 0,
+            });
+        unsafe { XFreeEventData(self.display, cookie) };
+        key_event
+    }
+
+    fn check_connection(&self) -> Result<(), X11Error> {
+        if self.is_connection_lost() {
+            Err(X11Error::ConnectionLost)
+        } else {
+            Ok(())
+        }
+    }
+
     /// # Panics
     ///
     /// Panics if the symbol string contains a null byte.
@@ -148,60 +647,87 @@ impl X11Handle {
         Some(keycode)
     }
 
-    /// Returns the character corresponding to the `X11KeyPress`.
+    /// Returns the text corresponding to the `X11KeyPress`.
     ///
     /// Can return both simple ASCII characters a, b, c, etc. or whole Unicode graphemes, depending
-    /// on the input.
+    /// on the input. For the presses returned by [`X11Handle::read_next_text_keypress_timeout`],
+    /// it can also be a character composed with a dead key, or text of several characters
+    /// committed by an input method.
     ///
     /// Control characters such as the arrow or modifier keys do not have a character representation,
     /// and for them None is returned.
     #[must_use]
-    #[expect(
-        clippy::cast_possible_truncation,
-        clippy::cast_possible_wrap,
-        reason = "buffer length is a small constant (4); the cast to c_int is always safe"
-    )]
     pub fn keypress_to_grapheme(&self, press: &X11KeyPress) -> Option<String> {
-        const BUFFER_LENGTH: usize = 4;
-        let mut char_buffer: [c_char; BUFFER_LENGTH] = [0; BUFFER_LENGTH];
-        let char_buffer_ptr = char_buffer.as_mut_ptr();
-
-        let bytes_returned = unsafe {
+        let mut buffer: Vec<u8> = vec![0; 16];
+        loop {
             let mut keysym_return = 0;
-            let status_return = ptr::null_mut();
-            Xutf8LookupString(
-                self.input_context,
-                &mut press.event.clone(),
-                char_buffer_ptr,
-                BUFFER_LENGTH as c_int,
-                &raw mut keysym_return,
-                status_return,
-            )
-        };
+            let mut status_return = 0;
+            let bytes_returned = unsafe {
+                Xutf8LookupString(
+                    self.input_context,
+                    &mut press.event.clone(),
+                    buffer.as_mut_ptr().cast::<c_char>(),
+                    c_int::try_from(buffer.len()).unwrap_or(c_int::MAX),
+                    &raw mut keysym_return,
+                    &raw mut status_return,
+                )
+            };
+            let length = usize::try_from(bytes_returned).unwrap_or(0);
 
-        // The input has no valid character representation. This for example occurs on presses
-        // of modifier and navigation keys.
-        if bytes_returned == 0 {
-            return None;
-        }
+            // The text did not fit, and its length was returned instead. The lookup is repeated
+            // with a buffer large enough, as the text committed by an input method can be long.
+            if status_return == XBufferOverflow {
+                buffer.resize(length, 0);
+                continue;
+            }
+
+            // The input has no valid character representation. This for example occurs on presses
+            // of modifier and navigation keys.
+            if length == 0 || (status_return != XLookupChars && status_return != XLookupBoth) {
+                return None;
+            }
 
-        // Converting the returned symbol into a character again.
-        let char_str = unsafe { CStr::from_ptr(char_buffer_ptr) };
-        Some(char_str.to_string_lossy().into_owned())
+            return Some(String::from_utf8_lossy(&buffer[..length]).into_owned());
+        }
     }
 
     /// Returns the key symbol name corresponding to the `X11KeyPress`.
     ///
-    /// The conversion is performed by looking up the key name based of the key code. This means all
-    /// modifier information is lost, and the returned symbol might not correspond to the one printed
-    /// onto the physical keycap.
+    /// The conversion is performed by looking up the key name based of the key code in the keyboard
+    /// layout active at the time of the press. The modifier information is lost, and the returned
+    /// symbol is the one of the key without any modifiers.
     ///
     /// For control characters a string representation of the key name is returned.
     #[must_use]
     pub fn keypress_to_key_name(&self, press: &X11KeyPress) -> Option<String> {
+        // The active layout group is carried in the 13th and 14th bits of the event state.
+        let group = (press.event.state >> 13) & 0b11;
+        let keysym = self
+            .keycode_to_keysym(press.keycode, group, 0)
+            .or_else(|| self.keycode_to_keysym(press.keycode, 0, 0))?;
+        Self::keysym_to_name(keysym)
+    }
+
+    /// Returns the key symbol of the key in the layout group at the shift level, or `None` if the
+    /// key has no symbol there.
+    #[must_use]
+    pub fn keycode_to_keysym(&self, keycode: u8, group: c_uint, level: c_uint) -> Option<KeySym> {
+        let keysym = unsafe {
+            XkbKeycodeToKeysym(
+                self.display,
+                keycode,
+                c_int::try_from(group).ok()?,
+                c_int::try_from(level).ok()?,
+            )
+        };
+        (keysym != 0).then_some(keysym)
+    }
+
+    /// Returns the name of the key symbol, such as `Return` or `a`.
+    #[must_use]
+    pub fn keysym_to_name(keysym: KeySym) -> Option<String> {
         unsafe {
-            let sym = XkbKeycodeToKeysym(self.display, press.keycode, 0, 0);
-            let symbol = XKeysymToString(sym);
+            let symbol = XKeysymToString(keysym);
 
             // Null is returned when the specified Keysym is not defined.
             if symbol.is_null() {
@@ -212,114 +738,270 @@ impl X11Handle {
         }
     }
 
+    /// Returns the keyboard layout group which is active now.
+    #[must_use]
+    pub fn active_group(&self) -> c_uint {
+        let mut state: XkbStateRec = unsafe { std::mem::zeroed() };
+        let status = unsafe { XkbGetState(self.display, XKB_USE_CORE_KEYBOARD, &raw mut state) };
+        if status == 0 {
+            c_uint::from(state.group)
+        } else {
+            0
+        }
+    }
+
+    /// Returns whether the keyboard mapping has changed since the previous call, such as when
+    /// another keyboard layout is set with `setxkbmap`. The keycodes of the key symbols may have
+    /// changed along with it.
+    #[must_use]
+    pub fn take_keymap_change(&self) -> bool {
+        self.keymap_changed.replace(false)
+    }
+
     /// Returns the XID of the currently focused window via `_NET_ACTIVE_WINDOW`, or `None` if
     /// the property is unavailable (e.g. no EWMH-compliant compositor is running).
-    ///
-    /// # Panics
-    ///
-    /// Panics if the `_NET_ACTIVE_WINDOW` atom cannot be interned.
     #[must_use]
-    #[expect(
-        clippy::cast_ptr_alignment,
-        reason = "X11 property data for _NET_ACTIVE_WINDOW is guaranteed to contain an XID-aligned value"
-    )]
     pub fn get_active_window(&self) -> Option<XID> {
-        let atom_name = CString::new("_NET_ACTIVE_WINDOW").unwrap();
-        let atom = unsafe { XInternAtom(self.display, atom_name.as_ptr(), 0) };
-        unsafe {
-            let mut prop: *mut u8 = ptr::null_mut();
-            let mut actual_type: Atom = 0;
-            let mut actual_format: c_int = 0;
-            let mut nitems: c_ulong = 0;
-            let mut bytes_after: c_ulong = 0;
+        let property = self.get_property(self.root_window, "_NET_ACTIVE_WINDOW", XA_WINDOW)?;
+        property.longs()?.first().copied()
+    }
 
-            let status = XGetWindowProperty(
-                self.display,
-                self.root_window,
-                atom,
-                0,
-                1,
-                0,
-                XA_WINDOW,
-                &raw mut actual_type,
-                &raw mut actual_format,
-                &raw mut nitems,
-                &raw mut bytes_after,
-                &raw mut prop,
-            );
+    /// Returns the `WM_CLASS` property of the window as `(instance_name, class_name)`, or `None`
+    /// if the property is absent. The two values are the null-separated parts of the raw property.
+    pub fn get_window_class(&self, window: XID) -> Option<(String, String)> {
+        let property = self.get_property(window, "WM_CLASS", XA_STRING)?;
 
-            if status != 0 || prop.is_null() || nitems == 0 {
-                if !prop.is_null() {
-                    XFree(prop.cast::<c_void>());
-                }
-                return None;
-            }
+        // WM_CLASS is two null-terminated strings concatenated: instance\0class\0
+        let data = property.bytes()?;
+        let mut parts = data.splitn(2, |&b| b == 0);
+        let instance = parts
+            .next()
+            .and_then(|s| std::str::from_utf8(s).ok())
+            .map(str::to_owned);
+        let class = parts
+            .next()
+            .map(|s| s.split(|&b| b == 0).next().unwrap_or(s))
+            .and_then(|s| std::str::from_utf8(s).ok())
+            .map(str::to_owned);
 
-            let window = *prop.cast::<XID>();
-            XFree(prop.cast::<c_void>());
-            Some(window)
-        }
+        instance.zip(class)
     }
 
-    /// Returns the `WM_CLASS` property of the window as `(instance_name, class_name)`, or `None`
-    /// if the property is absent. The two values are the null-separated parts of the raw property.
+    /// Returns the top-level client windows via `_NET_CLIENT_LIST` in their mapping order, or an
+    /// empty list if the property is unavailable (e.g. no EWMH-compliant window manager is running).
+    #[must_use]
+    pub fn get_client_list(&self) -> Vec<XID> {
+        self.get_property(self.root_window, "_NET_CLIENT_LIST", XA_WINDOW)
+            .and_then(|property| property.longs().map(<[XID]>::to_vec))
+            .unwrap_or_default()
+    }
+
+    /// Returns the title of the window from `_NET_WM_NAME`, falling back to the legacy `WM_NAME`
+    /// property for windows which do not set the former.
     ///
     /// # Panics
     ///
-    /// Panics if the `WM_CLASS` atom cannot be interned.
+    /// Panics only if the constant `UTF8_STRING` atom name contains a null byte, which it does not.
+    #[must_use]
+    pub fn get_window_title(&self, window: XID) -> Option<String> {
+        let utf8_string = CString::new("UTF8_STRING").unwrap();
+        let utf8_string = unsafe { XInternAtom(self.display, utf8_string.as_ptr(), 0) };
+
+        self.get_text_property(window, "_NET_WM_NAME", utf8_string)
+            .or_else(|| self.get_text_property(window, "WM_NAME", AnyPropertyType as Atom))
+    }
+
+    /// Returns the text of a property with 8-bit format, or `None` if the property is absent or
+    /// empty. Invalid UTF-8 is replaced rather than rejected.
+    fn get_text_property(
+        &self,
+        window: XID,
+        property: &str,
+        property_type: Atom,
+    ) -> Option<String> {
+        let property = self.get_property(window, property, property_type)?;
+        Some(String::from_utf8_lossy(property.bytes()?).into_owned())
+    }
+
+    /// Reads the whole property of the window, or `None` if it is absent, empty or of another
+    /// type. A larger request is made for as long as a part of the property is left out.
+    fn get_property(&self, window: XID, property: &str, property_type: Atom) -> Option<Property> {
+        let property_name = CString::new(property).unwrap();
+        let property = unsafe { XInternAtom(self.display, property_name.as_ptr(), 0) };
+
+        // The length of the request is counted in 32-bit units.
+        let mut length: c_ulong = 1024;
+        loop {
+            let data = self.fetch_property(window, property, property_type, length)?;
+            if data.bytes_after == 0 {
+                return (data.item_count > 0).then_some(data);
+            }
+            length += data.bytes_after.div_ceil(4);
+        }
+    }
+
+    /// Requests the given number of 32-bit units of the property from its beginning.
     #[expect(
         clippy::cast_possible_truncation,
-        reason = "X11 returns nitems as c_ulong; WM_CLASS data fits comfortably in usize on any platform"
+        reason = "X11 returns nitems as c_ulong; property data fits comfortably in usize on any platform"
     )]
-    pub fn get_window_class(&self, window: XID) -> Option<(String, String)> {
-        let prop_name = CString::new("WM_CLASS").unwrap();
-        let prop_atom = unsafe { XInternAtom(self.display, prop_name.as_ptr(), 0) };
-
-        unsafe {
-            let mut prop: *mut u8 = ptr::null_mut();
-            let mut actual_type: Atom = 0;
-            let mut actual_format: c_int = 0;
-            let mut nitems: c_ulong = 0;
-            let mut bytes_after: c_ulong = 0;
+    fn fetch_property(
+        &self,
+        window: XID,
+        property: Atom,
+        property_type: Atom,
+        length: c_ulong,
+    ) -> Option<Property> {
+        let mut data: *mut u8 = ptr::null_mut();
+        let mut actual_type: Atom = 0;
+        let mut actual_format: c_int = 0;
+        let mut nitems: c_ulong = 0;
+        let mut bytes_after: c_ulong = 0;
 
-            let status = XGetWindowProperty(
+        let status = unsafe {
+            XGetWindowProperty(
                 self.display,
                 window,
-                prop_atom,
+                property,
                 0,
-                1024,
+                c_long::try_from(length).unwrap_or(c_long::MAX),
                 0,
-                XA_STRING,
+                property_type,
                 &raw mut actual_type,
                 &raw mut actual_format,
                 &raw mut nitems,
                 &raw mut bytes_after,
-                &raw mut prop,
-            );
+                &raw mut data,
+            )
+        };
 
-            if status != 0 || prop.is_null() || nitems == 0 {
-                if !prop.is_null() {
-                    XFree(prop.cast::<c_void>());
-                }
-                return None;
-            }
+        // The data is freed along with the property even if the request failed.
+        let property = Property {
+            data,
+            format: actual_format,
+            item_count: nitems as usize,
+            bytes_after,
+        };
+        (status == 0).then_some(property)
+    }
+
+    /// Keeps count of the keys held down and of the keyboard mapping, returning the event if it is
+    /// a key press.
+    #[expect(
+        clippy::cast_possible_truncation,
+        reason = "X11 keycodes are 8-bit values stored in a wider integer by the FFI layer"
+    )]
+    fn track_key_event(&self, mut x_event: XEvent) -> Option<X11KeyPress> {
+        let event_type = x_event.get_type();
+        if Some(event_type) == self.xkb_event_type {
+            self.refresh_keymap(&mut x_event);
+            return None;
+        }
+        if event_type == MappingNotify {
+            unsafe { XRefreshKeyboardMapping(&raw mut x_event.mapping) };
+            self.forget_keymap();
+            return None;
+        }
+        if event_type != KeyPress && event_type != KeyRelease {
+            return None;
+        }
+
+        let event = XKeyEvent::from(x_event);
+        let keycode = event.keycode as u8;
+        let mut held_keycodes = self.held_keycodes.borrow_mut();
+        if event_type == KeyRelease {
+            held_keycodes.retain(|held| *held != keycode);
+            return None;
+        }
+
+        // The text which the input method composes or commits comes in presses without a keycode.
+        let is_repeat = keycode != 0 && held_keycodes.contains(&keycode);
+        if !is_repeat && keycode != 0 {
+            held_keycodes.push(keycode);
+        }
+        Some(X11KeyPress {
+            event,
+            modmask: event.state,
+            keycode,
+            is_repeat,
+        })
+    }
+
+    /// Reloads the keyboard mapping once the keyboard layout has changed.
+    fn refresh_keymap(&self, x_event: &mut XEvent) {
+        let xkb_event = std::ptr::from_mut(x_event);
+        let xkb_type = unsafe { (*xkb_event.cast::<XkbAnyEvent>()).xkb_type };
+        if xkb_type == XkbNewKeyboardNotify || xkb_type == XkbMapNotify {
+            unsafe { XkbRefreshKeyboardMapping(xkb_event.cast::<XkbMapNotifyEvent>()) };
+            self.forget_keymap();
+        }
+    }
+
+    /// Forgets what was looked up from the previous keyboard mapping, and marks it changed.
+    fn forget_keymap(&self) {
+        self.modifier_masks.borrow_mut().clear();
+        self.keymap_changed.set(true);
+    }
+
+    /// The releases of keys are missed while they are not grabbed, so the keys which are no
+    /// longer held down are forgotten before reading further events.
+    fn forget_released_keys(&self) {
+        let pressed = self.pressed_keycodes();
+        self.held_keycodes
+            .borrow_mut()
+            .retain(|held| pressed.contains(held));
+    }
 
-            // WM_CLASS is two null-terminated strings concatenated: instance\0class\0
-            let data = std::slice::from_raw_parts(prop, nitems as usize);
-            let mut parts = data.splitn(2, |&b| b == 0);
-            let instance = parts
-                .next()
-                .and_then(|s| std::str::from_utf8(s).ok())
-                .map(str::to_owned);
-            let class = parts
-                .next()
-                .map(|s| s.split(|&b| b == 0).next().unwrap_or(s))
-                .and_then(|s| std::str::from_utf8(s).ok())
-                .map(str::to_owned);
+    /// Selects the XKB events telling of keyboard layout changes, returning their event type.
+    fn select_keymap_events(display: *mut Display) -> Option<c_int> {
+        let mut opcode = 0;
+        let mut event_base = 0;
+        let mut error_base = 0;
+        let mut major = 1;
+        let mut minor = 0;
+        let is_supported = unsafe {
+            XkbQueryExtension(
+                display,
+                &raw mut opcode,
+                &raw mut event_base,
+                &raw mut error_base,
+                &raw mut major,
+                &raw mut minor,
+            )
+        };
+        if is_supported == 0 {
+            return None;
+        }
+
+        let mask = XkbNewKeyboardNotifyMask | XkbMapNotifyMask;
+        unsafe { XkbSelectEvents(display, XKB_USE_CORE_KEYBOARD, mask, mask) };
+        Some(event_base)
+    }
 
-            XFree(prop.cast::<c_void>());
-            instance.zip(class)
+    /// Returns the major opcode of the input extension if the server supports version 2.1 of it,
+    /// from which on the raw events are delivered regardless of the grabs of other clients.
+    fn query_input_extension(display: *mut Display) -> Option<c_int> {
+        let name = CString::new("XInputExtension").unwrap();
+        let mut opcode = 0;
+        let mut event_base = 0;
+        let mut error_base = 0;
+        let is_supported = unsafe {
+            XQueryExtension(
+                display,
+                name.as_ptr(),
+                &raw mut opcode,
+                &raw mut event_base,
+                &raw mut error_base,
+            )
+        };
+        if is_supported == 0 {
+            return None;
         }
+
+        let mut major = 2;
+        let mut minor = 1;
+        let status = unsafe { XIQueryVersion(display, &raw mut major, &raw mut minor) };
+        (status == 0 && (major, minor) >= (2, 1)).then_some(opcode)
     }
 
     fn read_next_event(&self) -> XEvent {
@@ -330,11 +1012,33 @@ impl X11Handle {
         event
     }
 
+    /// Has the I/O errors of the display mark the connection lost instead of exiting the process.
+    /// The exit handler is looked up at runtime, as libX11 has it only since version 1.7. Before
+    /// it, the I/O errors keep exiting the process.
+    fn watch_connection(display: *mut Display, connection_lost: &Cell<bool>) {
+        let symbol = unsafe { libc::dlsym(libc::RTLD_DEFAULT, c"XSetIOErrorExitHandler".as_ptr()) };
+        if symbol.is_null() {
+            return;
+        }
+
+        let set_exit_handler =
+            unsafe { std::mem::transmute::<*mut c_void, SetIOErrorExitHandler>(symbol) };
+        unsafe {
+            XSetIOErrorHandler(Some(ignore_io_error));
+            set_exit_handler(
+                display,
+                Some(mark_connection_lost),
+                ptr::from_ref(connection_lost).cast_mut().cast(),
+            );
+        }
+    }
+
     fn load_input_context(display: *mut Display, window: XID) -> Option<XIC> {
         let xim = Self::load_input_method(display)?;
         let xic = unsafe {
             let xn_input_style = CString::new(XNInputStyle).unwrap();
             let xn_client_window = CString::new(XNClientWindow).unwrap();
+            let xn_focus_window = CString::new(XNFocusWindow).unwrap();
 
             XCreateIC(
                 xim,
@@ -342,26 +1046,76 @@ impl X11Handle {
                 XIMPreeditNothing | XIMStatusNothing,
                 xn_client_window.as_ptr(),
                 window,
+                xn_focus_window.as_ptr(),
+                window,
                 ptr::null_mut::<c_void>(),
             )
         };
-        xic.into()
+        if xic.is_null() {
+            return None;
+        }
+
+        // The input method only composes text for the input contexts in focus.
+        unsafe { XSetICFocus(xic) };
+        Some(xic)
     }
 
+    /// Opens the input method of the locale, such as the one chosen with `XMODIFIERS`. Without a
+    /// dedicated one, the input method composes characters from the dead keys and compose
+    /// sequences of the locale.
     fn load_input_method(display: *mut Display) -> Option<XIM> {
+        unsafe {
+            // The input method follows the locale which the application has set.
+            if XSupportsLocale() != 0 {
+                XSetLocaleModifiers(c"".as_ptr());
+            }
+        }
+
         let xim = unsafe { XOpenIM(display, ptr::null_mut(), ptr::null_mut(), ptr::null_mut()) };
-        xim.into()
+        (!xim.is_null()).then_some(xim)
     }
 }
 
 impl Drop for X11Handle {
     fn drop(&mut self) {
+        // Closing a broken connection would write to it, running into another I/O error.
+        if self.is_connection_lost() {
+            return;
+        }
         unsafe {
             XCloseDisplay(self.display);
         }
     }
 }
 
+/// Every combination of Caps Lock and the Num Lock mask, each once. Without Num Lock mapped to a
+/// modifier, its mask is 0 and the combinations with it are the same as those without.
+fn lock_modifier_combinations(num_lock: c_uint) -> Vec<c_uint> {
+    let mut combinations = vec![0, LockMask, num_lock, LockMask | num_lock];
+    combinations.sort_unstable();
+    combinations.dedup();
+    combinations
+}
+
+#[cfg(test)]
+mod lock_modifier_tests {
+    use super::lock_modifier_combinations;
+    use x11::xlib::{LockMask, Mod2Mask};
+
+    #[test]
+    fn lock_modifiers_are_combined() {
+        assert_eq!(
+            lock_modifier_combinations(Mod2Mask),
+            vec![0, LockMask, Mod2Mask, LockMask | Mod2Mask]
+        );
+    }
+
+    #[test]
+    fn unmapped_num_lock_adds_no_combinations() {
+        assert_eq!(lock_modifier_combinations(0), vec![0, LockMask]);
+    }
+}
+
 #[cfg(test)]
 #[cfg(feature = "x11-tests")]
 mod tests {
@@ -371,7 +1125,7 @@ mod tests {
     #[test]
     #[serial]
     fn test_string_to_keycode() {
-        let x11 = X11Handle::new();
+        let x11 = X11Handle::new().unwrap();
         let keycode = x11.string_to_keycode("Return").unwrap();
         assert_eq!(keycode, 36)
     }
@@ -1,5 +1,5 @@
 mod handle;
 mod module;
 
-pub use handle::X11Handle;
+pub use handle::{X11Error, X11Handle, X11KeyPress};
 pub use module::X11Module;
@@ -1,17 +1,15 @@
-use crate::handle::X11Handle;
+use crate::handle::{X11Error, X11Handle};
 
 pub struct X11Module {
     pub x11_handle: X11Handle,
 }
 
 impl X11Module {
-    #[expect(
-        clippy::new_without_default,
-        reason = "delegates to X11Handle::new() which opens the X11 display; Default would hide this"
-    )]
-    #[must_use]
-    pub fn new() -> Self {
-        let x11_handle = X11Handle::new();
-        Self { x11_handle }
+    /// # Errors
+    ///
+    /// Returns an error if the X11 display cannot be opened.
+    pub fn new() -> Result<Self, X11Error> {
+        let x11_handle = X11Handle::new()?;
+        Ok(Self { x11_handle })
     }
 }
@@ -0,0 +1,25 @@
+use longcut_core::model::selection::Selection;
+use longcut_core::port::clipboard::Clipboard;
+use longcut_xcb::{Selection as XcbSelection, XcbService};
+
+pub struct XcbClipboard<'a> {
+    xcb: &'a XcbService,
+}
+
+impl<'a> XcbClipboard<'a> {
+    #[must_use]
+    pub fn new(xcb: &'a XcbService) -> Self {
+        Self { xcb }
+    }
+}
+
+impl Clipboard for XcbClipboard<'_> {
+    fn read(&self, selection: Selection) -> Option<String> {
+        let selection = match selection {
+            Selection::Clipboard => XcbSelection::Clipboard,
+            Selection::Primary => XcbSelection::Primary,
+        };
+
+        self.xcb.read_selection(selection)
+    }
+}
@@ -0,0 +1,3 @@
+mod clipboard;
+
+pub use clipboard::XcbClipboard;
@@ -7,8 +7,8 @@ use longcut_graphics_lib::model::position::Position;
 use longcut_graphics_lib::port::renderer::Renderer;
 use longcut_gui::WindowProperties;
 use longcut_gui::port::window_manager::{RenderPassFn, WindowManager};
-use longcut_xcb::{Window, XcbService};
-use std::cell::RefCell;
+use longcut_xcb::{Window, XcbError, XcbService};
+use std::cell::{Cell, RefCell};
 
 pub struct XcbWindowManager<'a> {
     xcb: &'a XcbService,
@@ -54,50 +54,61 @@ impl<'a> XcbWindowManager<'a> {
 
         (window_size, window_position)
     }
-}
 
-impl WindowManager for XcbWindowManager<'_> {
-    fn show_window(&self, requested_properties: WindowProperties, callback: RenderPassFn) {
-        let (dimensions, position) = self.calculate_window_geometry(&requested_properties);
+    fn try_show_window(
+        &self,
+        requested_properties: &WindowProperties,
+        callback: RenderPassFn,
+    ) -> Result<(), XcbError> {
+        let (dimensions, position) = self.calculate_window_geometry(requested_properties);
 
         let mut window_opt = self.window.borrow_mut();
 
         // Recreate the window if geometry has changed.
-        if let Some(window) = window_opt.as_ref() {
-            let (w, h) = window.size();
-            let (x, y) = window.position();
-            if w != dimensions.width
-                || h != dimensions.height
-                || x != position.horizontal
-                || y != position.vertical
+        let window = match window_opt.take() {
+            Some(window)
+                if window.size() == (dimensions.width, dimensions.height)
+                    && window.position() == (position.horizontal, position.vertical) =>
             {
-                *window_opt = None;
+                window
             }
-        }
-
-        if window_opt.is_none() {
-            *window_opt = Some(self.xcb.create_window(
-                position.horizontal,
-                position.vertical,
-                dimensions.width,
-                dimensions.height,
-            ));
-        }
-
-        let window = window_opt.as_ref().unwrap();
+            outdated => {
+                drop(outdated);
+                self.xcb.create_window(
+                    position.horizontal,
+                    position.vertical,
+                    dimensions.width,
+                    dimensions.height,
+                )?
+            }
+        };
+        let window = window_opt.insert(window);
         let (w, h) = window.size();
 
         window.show(move |cr, _w, _h| {
             let cairo_renderer = CairoRenderer::new(cr);
             let render_area_dimensions = Dimensions::new(w, h);
             callback(render_area_dimensions, &cairo_renderer);
-        });
+            cairo_renderer.into_result()
+        })
+    }
+}
+
+impl WindowManager for XcbWindowManager<'_> {
+    fn show_window(&self, requested_properties: WindowProperties, callback: RenderPassFn) {
+        if let Err(error) = self.try_show_window(&requested_properties, callback) {
+            // The window may be left broken, so a new one is created for the next time.
+            *self.window.borrow_mut() = None;
+            eprintln!("Failed to show window: {error:?}");
+        }
     }
 
     fn hide_window(&self) {
         let window_opt = self.window.borrow();
-        if let Some(window) = window_opt.as_ref() {
-            window.hide();
+        if let Some(window) = window_opt.as_ref()
+            && let Err(error) = window.hide()
+        {
+            eprintln!("Failed to hide window: {error:?}");
         }
     }
 }
@@ -109,11 +120,30 @@ impl WindowManager for XcbWindowManager<'_> {
 #[derive(Debug)]
 struct CairoRenderer<'a> {
     cairo_context: &'a cairo::Context,
+    /// The first error cairo failed to draw with. Drawing carries on regardless, as the
+    /// [Renderer] has no way to fail.
+    error: Cell<Option<cairo::Error>>,
 }
 
 impl<'a> CairoRenderer<'a> {
     fn new(cairo_context: &'a cairo::Context) -> Self {
-        CairoRenderer { cairo_context }
+        CairoRenderer {
+            cairo_context,
+            error: Cell::new(None),
+        }
+    }
+
+    /// Whether everything was drawn without errors, or the first error otherwise.
+    fn into_result(self) -> Result<(), cairo::Error> {
+        self.error.get().map_or(Ok(()), Err)
+    }
+
+    fn record_error(&self, result: Result<(), cairo::Error>) {
+        if let Err(error) = result
+            && self.error.get().is_none()
+        {
+            self.error.set(Some(error));
+        }
     }
 
     fn set_font_family(&self, font_family: &str) {
@@ -140,7 +170,7 @@ impl Renderer for CairoRenderer<'_> {
             f64::from(size.width),
             f64::from(size.height),
         );
-        self.cairo_context.fill().unwrap();
+        self.record_error(self.cairo_context.fill());
     }
 
     fn draw_text(&self, color: &Color, position: &Position, font: &Font, text: &str) {
@@ -153,19 +183,29 @@ impl Renderer for CairoRenderer<'_> {
             f64::from(position.horizontal),
             f64::from(position.vertical + u32::from(font.size)),
         );
-        self.cairo_context.show_text(text).unwrap();
+        self.record_error(self.cairo_context.show_text(text));
     }
 
     fn measure_text(&self, font: &Font, text: &str) -> Dimensions {
         self.set_font_family(&font.family);
         self.set_font_size(f64::from(font.size));
-        let font_extents = self.cairo_context.font_extents().unwrap();
-        let text_extents = self.cairo_context.text_extents(text).unwrap();
-        #[expect(
-            clippy::cast_possible_truncation,
-            clippy::cast_sign_loss,
-            reason = "cairo pixel measurements are always small positive values"
-        )]
-        Dimensions::new(text_extents.width() as u32, font_extents.height() as u32)
+        let extents = self
+            .cairo_context
+            .font_extents()
+            .and_then(|font_extents| Ok((font_extents, self.cairo_context.text_extents(text)?)));
+        match extents {
+            #[expect(
+                clippy::cast_possible_truncation,
+                clippy::cast_sign_loss,
+                reason = "cairo pixel measurements are always small positive values"
+            )]
+            Ok((font_extents, text_extents)) => {
+                Dimensions::new(text_extents.width() as u32, font_extents.height() as u32)
+            }
+            Err(error) => {
+                self.record_error(Err(error));
+                Dimensions::new(0, 0)
+            }
+        }
     }
 }
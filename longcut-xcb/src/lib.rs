@@ -1,8 +1,10 @@
 mod module;
+mod selection;
 mod service;
 mod visual;
 mod window;
 
 pub use module::XcbModule;
-pub use service::XcbService;
+pub use selection::Selection;
+pub use service::{XcbError, XcbService};
 pub use window::Window;
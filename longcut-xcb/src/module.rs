@@ -1,4 +1,4 @@
-use crate::service::XcbService;
+use crate::service::{XcbError, XcbService};
 use longcut_config::Module;
 
 pub struct XcbModule {
@@ -12,13 +12,11 @@ impl Module for XcbModule {
 }
 
 impl XcbModule {
-    #[expect(
-        clippy::new_without_default,
-        reason = "delegates to XcbService::new() which connects to the X server; Default would hide this"
-    )]
-    #[must_use]
-    pub fn new() -> Self {
-        let xcb_service = XcbService::new();
-        XcbModule { xcb_service }
+    /// # Errors
+    ///
+    /// Returns an error if the connection to the X server cannot be established.
+    pub fn new() -> Result<Self, XcbError> {
+        let xcb_service = XcbService::new()?;
+        Ok(XcbModule { xcb_service })
     }
 }
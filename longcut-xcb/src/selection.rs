@@ -0,0 +1,108 @@
+use std::thread;
+use std::time::{Duration, Instant};
+use x11rb::connection::Connection;
+use x11rb::protocol::Event;
+use x11rb::protocol::xproto::{Atom, AtomEnum, ConnectionExt, CreateWindowAux, WindowClass};
+use x11rb::xcb_ffi::XCBConnection;
+use x11rb::{COPY_DEPTH_FROM_PARENT, COPY_FROM_PARENT, CURRENT_TIME, NONE};
+
+/// How long the owner of a selection is waited for to provide its contents.
+const SELECTION_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// How often the connection is checked for the contents of the selection.
+const POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+/// Selections holding text copied by the user.
+#[derive(Clone, Copy, Debug)]
+pub enum Selection {
+    Clipboard,
+    Primary,
+}
+
+impl Selection {
+    fn atom_name(self) -> &'static [u8] {
+        match self {
+            Selection::Clipboard => b"CLIPBOARD",
+            Selection::Primary => b"PRIMARY",
+        }
+    }
+}
+
+/// Reads the contents of the selection as UTF-8 text.
+///
+/// The selection owner delivers the contents to a property of a window, so a temporary window is
+/// created for the duration of the request.
+pub(crate) fn read_selection(
+    conn: &XCBConnection,
+    root: u32,
+    selection: Selection,
+) -> Option<String> {
+    let window = conn.generate_id().ok()?;
+    conn.create_window(
+        COPY_DEPTH_FROM_PARENT,
+        window,
+        root,
+        0,
+        0,
+        1,
+        1,
+        0,
+        WindowClass::INPUT_ONLY,
+        COPY_FROM_PARENT,
+        &CreateWindowAux::new(),
+    )
+    .ok()?;
+
+    let text = request_text(conn, window, selection);
+
+    let _ = conn.destroy_window(window);
+    let _ = conn.flush();
+
+    text
+}
+
+fn request_text(conn: &XCBConnection, window: u32, selection: Selection) -> Option<String> {
+    let selection = intern_atom(conn, selection.atom_name())?;
+    let target = intern_atom(conn, b"UTF8_STRING")?;
+    let incremental = intern_atom(conn, b"INCR")?;
+    let property = intern_atom(conn, b"LONGCUT_SELECTION")?;
+
+    conn.convert_selection(window, selection, target, property, CURRENT_TIME)
+        .ok()?;
+    conn.flush().ok()?;
+
+    // No windows of the connection listen to other events, so the events preceding the
+    // notification can be discarded.
+    let deadline = Instant::now() + SELECTION_TIMEOUT;
+    while Instant::now() < deadline {
+        match conn.poll_for_event().ok()? {
+            Some(Event::SelectionNotify(event)) if event.requestor == window => {
+                // The selection is empty, or its owner cannot provide it as text.
+                if event.property == NONE {
+                    return None;
+                }
+
+                let reply = conn
+                    .get_property(true, window, property, AtomEnum::ANY, 0, u32::MAX)
+                    .ok()?
+                    .reply()
+                    .ok()?;
+
+                // Large selections are transferred in chunks, which is not supported.
+                if reply.type_ == incremental {
+                    return None;
+                }
+
+                return String::from_utf8(reply.value).ok();
+            }
+            Some(_) => {}
+            None => thread::sleep(POLL_INTERVAL),
+        }
+    }
+
+    None
+}
+
+fn intern_atom(conn: &XCBConnection, name: &[u8]) -> Option<Atom> {
+    Some(conn.intern_atom(false, name).ok()?.reply().ok()?.atom)
+}
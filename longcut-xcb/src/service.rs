@@ -1,5 +1,7 @@
+use crate::selection::{Selection, read_selection};
 use crate::window::Window;
 use x11rb::connection::Connection;
+use x11rb::errors::{ConnectionError, ReplyError, ReplyOrIdError};
 use x11rb::protocol::xproto::Screen;
 use x11rb::xcb_ffi::XCBConnection;
 
@@ -8,22 +10,55 @@ pub struct XcbService {
     screen_num: usize,
 }
 
+#[derive(Debug)]
+pub enum XcbError {
+    /// The connection to the X server could not be established.
+    Connect(String),
+    /// A request to the X server failed, such as when the connection to it broke.
+    Request(String),
+    /// The screen has no visual to create windows with.
+    NoVisual,
+    /// Cairo failed to render the window content.
+    Render(String),
+}
+
+impl From<ConnectionError> for XcbError {
+    fn from(error: ConnectionError) -> Self {
+        Self::Request(error.to_string())
+    }
+}
+
+impl From<ReplyError> for XcbError {
+    fn from(error: ReplyError) -> Self {
+        Self::Request(error.to_string())
+    }
+}
+
+impl From<ReplyOrIdError> for XcbError {
+    fn from(error: ReplyOrIdError) -> Self {
+        Self::Request(error.to_string())
+    }
+}
+
+impl From<cairo::Error> for XcbError {
+    fn from(error: cairo::Error) -> Self {
+        Self::Render(error.to_string())
+    }
+}
+
 impl XcbService {
-    /// # Panics
+    /// Connects to the X server of the `DISPLAY` environment variable.
     ///
-    /// Panics if the connection to the X server cannot be established.
-    #[expect(
-        clippy::new_without_default,
-        reason = "connects to the X server on construction; Default would hide this side effect"
-    )]
-    #[must_use]
-    pub fn new() -> Self {
+    /// # Errors
+    ///
+    /// Returns an error if the connection to the X server cannot be established.
+    pub fn new() -> Result<Self, XcbError> {
         let (connection, screen_num) =
-            XCBConnection::connect(None).expect("Failed to connect to X server");
-        XcbService {
+            XCBConnection::connect(None).map_err(|e| XcbError::Connect(e.to_string()))?;
+        Ok(XcbService {
             connection,
             screen_num,
-        }
+        })
     }
 
     pub fn screen(&self) -> &Screen {
@@ -55,11 +90,27 @@ impl XcbService {
         )
     }
 
+    /// Reads the text of the selection. Returns nothing if the selection is empty, does not hold
+    /// text, or its owner does not respond in time.
+    #[must_use]
+    pub fn read_selection(&self, selection: Selection) -> Option<String> {
+        read_selection(&self.connection, self.screen().root, selection)
+    }
+
+    /// # Errors
+    ///
+    /// Returns an error if the window cannot be created.
     #[expect(
         clippy::cast_possible_truncation,
         reason = "values are clamped with .min() before casting, so truncation cannot occur"
     )]
-    pub fn create_window(&self, x: u32, y: u32, width: u32, height: u32) -> Window<'_> {
+    pub fn create_window(
+        &self,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<Window<'_>, XcbError> {
         Window::new(
             &self.connection,
             self.screen(),
@@ -1,3 +1,4 @@
+use crate::service::XcbError;
 use crate::visual::{CXcbVisualtype, find_argb_visual, find_root_visual};
 use x11rb::connection::Connection;
 use x11rb::protocol::xproto::{
@@ -22,9 +23,9 @@ pub struct Window<'a> {
 }
 
 impl<'a> Window<'a> {
-    /// # Panics
+    /// # Errors
     ///
-    /// Panics if window or colormap creation fails.
+    /// Returns an error if the window or its colormap cannot be created.
     #[expect(
         clippy::cast_sign_loss,
         reason = "coordinates originate from clamped non-negative u32 values, so i16-to-u32 is lossless"
@@ -36,21 +37,20 @@ impl<'a> Window<'a> {
         y: i16,
         width: u16,
         height: u16,
-    ) -> Self {
+    ) -> Result<Self, XcbError> {
         let (visual, depth) = if let Some(v) = find_argb_visual(screen) {
             (v, 32u8)
         } else {
             (
-                find_root_visual(screen).expect("No root visual found"),
+                find_root_visual(screen).ok_or(XcbError::NoVisual)?,
                 screen.root_depth,
             )
         };
 
-        let colormap = conn.generate_id().expect("Failed to generate colormap ID");
-        conn.create_colormap(ColormapAlloc::NONE, colormap, screen.root, visual.visual_id)
-            .expect("Failed to create colormap");
+        let colormap = conn.generate_id()?;
+        conn.create_colormap(ColormapAlloc::NONE, colormap, screen.root, visual.visual_id)?;
 
-        let window_id = conn.generate_id().expect("Failed to generate window ID");
+        let window_id = conn.generate_id()?;
         let win_aux = CreateWindowAux::new()
             .border_pixel(0)
             .override_redirect(1)
@@ -68,38 +68,47 @@ impl<'a> Window<'a> {
             WindowClass::INPUT_OUTPUT,
             visual.visual_id,
             &win_aux,
-        )
-        .expect("Failed to create window");
+        )?;
+
+        // From here on, dropping the window frees the resources if setting it up fails.
+        let window = Window {
+            conn,
+            id: window_id,
+            colormap,
+            x: x as u32,
+            y: y as u32,
+            width: u32::from(width),
+            height: u32::from(height),
+            visual,
+        };
 
         // Set _NET_WM_WINDOW_TYPE = _NET_WM_WINDOW_TYPE_DOCK
-        let wm_type = intern_atom(conn, b"_NET_WM_WINDOW_TYPE");
-        let dock_type = intern_atom(conn, b"_NET_WM_WINDOW_TYPE_DOCK");
+        let wm_type = intern_atom(conn, b"_NET_WM_WINDOW_TYPE")?;
+        let dock_type = intern_atom(conn, b"_NET_WM_WINDOW_TYPE_DOCK")?;
         conn.change_property32(
             PropMode::REPLACE,
             window_id,
             wm_type,
             AtomEnum::ATOM,
             &[dock_type],
-        )
-        .expect("Failed to set window type");
+        )?;
 
         // Set _NET_WM_STATE = _NET_WM_STATE_ABOVE
-        let wm_state = intern_atom(conn, b"_NET_WM_STATE");
-        let above_state = intern_atom(conn, b"_NET_WM_STATE_ABOVE");
+        let wm_state = intern_atom(conn, b"_NET_WM_STATE")?;
+        let above_state = intern_atom(conn, b"_NET_WM_STATE_ABOVE")?;
         conn.change_property32(
             PropMode::REPLACE,
             window_id,
             wm_state,
             AtomEnum::ATOM,
             &[above_state],
-        )
-        .expect("Failed to set window state");
+        )?;
 
         // Set WM_HINTS with input = false (no-focus).
         // WM_HINTS format: flags(u32), input(u32), initial_state(u32), ...
         // InputHint flag = bit 0 (value 1), input = 0 (don't take focus).
         // Per ICCCM, WM_HINTS is a self-typed property (type atom = property atom).
-        let wm_hints = intern_atom(conn, b"WM_HINTS");
+        let wm_hints = intern_atom(conn, b"WM_HINTS")?;
         let hints_data: [u32; 9] = [1, 0, 0, 0, 0, 0, 0, 0, 0];
         conn.change_property32(
             PropMode::REPLACE,
@@ -107,75 +116,66 @@ impl<'a> Window<'a> {
             wm_hints,
             wm_hints,
             &hints_data,
-        )
-        .expect("Failed to set WM hints");
+        )?;
 
-        conn.flush().expect("Failed to flush connection");
+        conn.flush()?;
 
-        Window {
-            conn,
-            id: window_id,
-            colormap,
-            x: x as u32,
-            y: y as u32,
-            width: u32::from(width),
-            height: u32::from(height),
-            visual,
-        }
+        Ok(window)
     }
 
-    /// # Panics
+    /// # Errors
     ///
-    /// Panics if the rendering surface or context cannot be created.
+    /// Returns an error if the content cannot be rendered, or the window cannot be shown.
     #[expect(
         clippy::cast_possible_wrap,
         reason = "dimensions originate from u16 values, so u32-to-i32 is always within range"
     )]
-    pub fn show(&self, render_fn: impl FnOnce(&cairo::Context, u32, u32)) {
+    pub fn show(
+        &self,
+        render_fn: impl FnOnce(&cairo::Context, u32, u32) -> Result<(), cairo::Error>,
+    ) -> Result<(), XcbError> {
         let w = self.width as i32;
         let h = self.height as i32;
 
         // Render to an off-screen ImageSurface.
-        let image = cairo::ImageSurface::create(cairo::Format::ARgb32, w, h).expect("ImageSurface");
+        let image = cairo::ImageSurface::create(cairo::Format::ARgb32, w, h)?;
         {
-            let cr = cairo::Context::new(&image).expect("cairo context");
-            render_fn(&cr, self.width, self.height);
+            let cr = cairo::Context::new(&image)?;
+            render_fn(&cr, self.width, self.height)?;
         }
 
         // Map the window first so the compositor redirects it and allocates its buffer.
-        self.conn.map_window(self.id).expect("Failed to map window");
+        self.conn.map_window(self.id)?;
 
         // Raise the window to the top of the stacking order. Override-redirect windows are not
         // managed by the window manager, so EWMH hints like _NET_WM_STATE_ABOVE have no effect.
         // We must explicitly raise the window each time it is shown.
-        self.conn
-            .configure_window(
-                self.id,
-                &ConfigureWindowAux::new().stack_mode(StackMode::ABOVE),
-            )
-            .expect("Failed to raise window");
+        self.conn.configure_window(
+            self.id,
+            &ConfigureWindowAux::new().stack_mode(StackMode::ABOVE),
+        )?;
 
-        self.conn.flush().expect("Failed to flush");
+        self.conn.flush()?;
 
         // Blit the pre-rendered content to the now-mapped window via a cairo XCB surface.
-        let surface = self.create_xcb_surface(w, h);
-        let cr = cairo::Context::new(&surface).expect("blit context");
-        cr.set_source_surface(&image, 0.0, 0.0).expect("source");
-        cr.paint().expect("paint");
+        let surface = self.create_xcb_surface(w, h)?;
+        let cr = cairo::Context::new(&surface)?;
+        cr.set_source_surface(&image, 0.0, 0.0)?;
+        cr.paint()?;
         drop(cr);
         surface.flush();
         drop(surface);
-        self.conn.flush().expect("Failed to flush");
+        self.conn.flush()?;
+        Ok(())
     }
 
-    /// # Panics
+    /// # Errors
     ///
-    /// Panics if the window cannot be unmapped.
-    pub fn hide(&self) {
-        self.conn
-            .unmap_window(self.id)
-            .expect("Failed to unmap window");
-        self.conn.flush().expect("Failed to flush");
+    /// Returns an error if the window cannot be unmapped.
+    pub fn hide(&self) -> Result<(), XcbError> {
+        self.conn.unmap_window(self.id)?;
+        self.conn.flush()?;
+        Ok(())
     }
 
     #[must_use]
@@ -193,7 +193,7 @@ impl<'a> Window<'a> {
     /// All unsafe bridging between x11rb and cairo is confined here: the `CXcbVisualtype` lives
     /// on the stack and is guaranteed to outlive the `XCBVisualType` pointer wrapper derived
     /// from it.
-    fn create_xcb_surface(&self, width: i32, height: i32) -> cairo::XCBSurface {
+    fn create_xcb_surface(&self, width: i32, height: i32) -> Result<cairo::XCBSurface, XcbError> {
         let raw_conn = self.conn.get_raw_xcb_connection();
         // SAFETY: x11rb's XCBConnection wraps the same libxcb xcb_connection_t that cairo
         // expects. The connection is owned by XcbService and outlives this surface usage.
@@ -209,8 +209,13 @@ impl<'a> Window<'a> {
             )
         };
 
-        cairo::XCBSurface::create(&xcb_conn, &xcb_drawable, &xcb_visual, width, height)
-            .expect("XCB surface")
+        Ok(cairo::XCBSurface::create(
+            &xcb_conn,
+            &xcb_drawable,
+            &xcb_visual,
+            width,
+            height,
+        )?)
     }
 }
 
@@ -222,10 +227,6 @@ impl Drop for Window<'_> {
     }
 }
 
-fn intern_atom(conn: &XCBConnection, name: &[u8]) -> u32 {
-    conn.intern_atom(false, name)
-        .expect("Failed to send intern atom request")
-        .reply()
-        .expect("Failed to intern atom")
-        .atom
+fn intern_atom(conn: &XCBConnection, name: &[u8]) -> Result<u32, XcbError> {
+    Ok(conn.intern_atom(false, name)?.reply()?.atom)
 }